@@ -0,0 +1,96 @@
+//! Typed log-entry model for the tool-call/tool-response stream that
+//! `collect_codex_response_with_tools` collects from `EventMsg`s.
+//!
+//! That stream used to be built as ad hoc `serde_json::json!({...})` blobs
+//! with a stringly-typed `"type": "function"|"system"` field and
+//! hand-formatted ids like `format!("event_background_{ts}")`, each one
+//! serialized with its own `unwrap_or_default()`. `LogEntry` replaces that
+//! with a tagged enum: every `EventMsg` arm builds one typed value, and
+//! `LogEntry::into_json` is the single place that turns it into the
+//! `serde_json::Value` the rest of the pipeline (the checkpoint files,
+//! `read_session_log`, `build_readable_context`) already expects. The `kind`
+//! tag also gives us a stable schema for emitting this stream as NDJSON for
+//! replay/audit later, without having to reverse-engineer the old ad hoc
+//! shapes.
+//!
+//! `ToolResponse`/`ApprovalDecision`/`PatchApplyEnd` keep a literal
+//! `role: "tool"` field alongside the tag: those are the entries that end up
+//! pushed straight into `conversation_log` as their own `{"role": "tool",
+//! ...}` message, and `build_readable_context` matches on that field.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LogEntry {
+    ToolCall {
+        id: String,
+        /// Mirrors the old ad hoc `"type"` field: `"function"` for a real
+        /// tool call, `"system"` for a synthesized bookkeeping event
+        /// (`TaskStarted`, `TokenCount`, `BackgroundEvent`).
+        #[serde(rename = "type")]
+        call_type: &'static str,
+        name: String,
+        arguments: serde_json::Value,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        local_socket: Option<String>,
+    },
+    ToolResponse {
+        role: &'static str,
+        tool_call_id: String,
+        content: String,
+    },
+    ApprovalRequest {
+        id: String,
+        command: Vec<String>,
+        cwd: String,
+        reason: Option<String>,
+    },
+    ApprovalDecision {
+        role: &'static str,
+        tool_call_id: String,
+        approved: bool,
+        reasoning: String,
+        confidence: Option<f64>,
+        content: String,
+    },
+    PatchApplyBegin {
+        id: String,
+        detail: serde_json::Value,
+    },
+    PatchApplyEnd {
+        role: &'static str,
+        tool_call_id: String,
+        content: String,
+    },
+}
+
+impl LogEntry {
+    pub fn tool_call(id: String, name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        LogEntry::ToolCall { id, call_type: "function", name: name.into(), arguments, local_socket: None }
+    }
+
+    pub fn tool_call_with_socket(id: String, name: impl Into<String>, arguments: serde_json::Value, local_socket: String) -> Self {
+        LogEntry::ToolCall { id, call_type: "function", name: name.into(), arguments, local_socket: Some(local_socket) }
+    }
+
+    pub fn system_event(id: String, name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        LogEntry::ToolCall { id, call_type: "system", name: name.into(), arguments, local_socket: None }
+    }
+
+    pub fn tool_response(tool_call_id: String, content: String) -> Self {
+        LogEntry::ToolResponse { role: "tool", tool_call_id, content }
+    }
+
+    pub fn approval_decision(tool_call_id: String, approved: bool, reasoning: String, confidence: Option<f64>, content: String) -> Self {
+        LogEntry::ApprovalDecision { role: "tool", tool_call_id, approved, reasoning, confidence, content }
+    }
+
+    /// Turns this entry into the `serde_json::Value` shape the rest of the
+    /// pipeline consumes, or `Value::Null` if serialization fails. This is
+    /// the one place a whole entry is serialized, instead of at every call
+    /// site that used to build its own blob.
+    pub fn into_json(self) -> serde_json::Value {
+        serde_json::to_value(&self).unwrap_or(serde_json::Value::Null)
+    }
+}
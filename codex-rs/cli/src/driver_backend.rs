@@ -0,0 +1,725 @@
+//! Pluggable external-LLM backends for the autonomous driver.
+//!
+//! The driver loop and `generate_user_prompt`'s approval calls used to talk
+//! to a single OpenAI-shaped `ModelClient` regardless of `--driver-model`.
+//! [`DriverBackend`] lets each provider own its own request/response
+//! encoding (OpenAI function calls vs. Anthropic content blocks vs. Cohere
+//! tool calls) behind one interface, the same way [`crate::local_socket`]
+//! and the `exec` crate's `SupervisorTransport` keep one call site working
+//! across backends.
+//!
+//! On top of the three built-in vendor guesses, [`DriverProviderConfig`]
+//! lets an operator point the OpenAI-wire path at their own endpoint (a
+//! local Ollama, an air-gapped Anthropic-compatible proxy, etc.) via a
+//! `driver_providers` table in the task config YAML, carrying its own
+//! `base_url`, `env_key`, `wire_api`, and `http_headers` -- the same knobs
+//! `ModelProviderInfo` already exposes -- without recompiling.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// One function call a driver backend extracted from a model response.
+#[derive(Debug, Clone)]
+pub struct DriverFunctionCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+/// A driver/backend call failure, split into whether retrying the exact same
+/// request could plausibly succeed. A timeout, a 429, a 5xx, or a stream
+/// that dropped mid-response are `Transient`; an auth failure, a malformed
+/// request, or an empty response are `Permanent` -- retrying those just
+/// burns the retry budget for no reason. Callers (see `review_loop`'s
+/// retry-with-backoff) use this split to decide whether to retry or give up
+/// immediately.
+#[derive(Debug)]
+pub enum DriverError {
+    Transient(String),
+    Permanent(String),
+}
+
+impl DriverError {
+    pub fn is_transient(&self) -> bool {
+        matches!(self, DriverError::Transient(_))
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            DriverError::Transient(msg) | DriverError::Permanent(msg) => msg,
+        }
+    }
+}
+
+impl std::fmt::Display for DriverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DriverError::Transient(msg) => write!(f, "transient driver error: {msg}"),
+            DriverError::Permanent(msg) => write!(f, "permanent driver error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for DriverError {}
+
+/// Classifies a `reqwest` response/transport failure using the same signal
+/// an HTTP client library would: connection resets and timeouts are worth
+/// retrying, as are 429/5xx; everything else (4xx other than 429) is treated
+/// as permanent since retrying an identical malformed/unauthorized request
+/// just repeats the failure.
+fn classify_reqwest_error(err: &reqwest::Error) -> DriverError {
+    if err.is_timeout() || err.is_connect() {
+        return DriverError::Transient(err.to_string());
+    }
+    if let Some(status) = err.status() {
+        if status.as_u16() == 429 || status.is_server_error() {
+            return DriverError::Transient(format!("HTTP {status}: {err}"));
+        }
+        return DriverError::Permanent(format!("HTTP {status}: {err}"));
+    }
+    DriverError::Transient(err.to_string())
+}
+
+/// Classifies an error surfaced through `codex_core::client::ModelClient`
+/// (itself `anyhow::Error`-typed, so there's no status code to inspect
+/// directly), by its message text: an empty response or an auth failure
+/// (`unauthorized`, an invalid API key, `401`) is permanent -- retrying
+/// won't produce a different result -- and everything else
+/// (stream drops, timeouts, rate limits surfaced as plain text) is treated
+/// as transient, since that's the more common failure mode for a live
+/// streaming call and the cheaper one to get wrong.
+fn classify_model_client_error(err: &anyhow::Error) -> DriverError {
+    let msg = err.to_string();
+    let lower = msg.to_lowercase();
+    if lower.contains("empty response")
+        || lower.contains("unauthorized")
+        || lower.contains("invalid api key")
+        || lower.contains("invalid_api_key")
+        || lower.contains("401")
+    {
+        DriverError::Permanent(msg)
+    } else {
+        DriverError::Transient(msg)
+    }
+}
+
+/// One entry from the task config's `driver_providers:` table, naming a
+/// custom OpenAI-wire endpoint the driver/approval model can be pointed at
+/// by `--driver-provider <name>` instead of the `openai`/`anthropic`/
+/// `cohere` built-ins.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct DriverProviderConfig {
+    pub name: String,
+    pub base_url: String,
+    #[serde(default)]
+    pub env_key: Option<String>,
+    /// `"chat"` or `"responses"`, matching `codex_core::model_provider_info::WireApi`.
+    #[serde(default = "default_wire_api")]
+    pub wire_api: String,
+    #[serde(default)]
+    pub http_headers: Option<HashMap<String, String>>,
+}
+
+fn default_wire_api() -> String {
+    "chat".to_string()
+}
+
+/// Parses the optional `driver_providers:` table out of the task config
+/// YAML (the same file `{config_yaml}` is injected from). A missing table
+/// or an entry that doesn't parse is dropped rather than failing the whole
+/// run -- provider selection always has the built-in guess to fall back on.
+pub fn load_driver_providers(config_yaml: &str) -> Vec<DriverProviderConfig> {
+    let Ok(doc) = serde_yaml::from_str::<serde_yaml::Value>(config_yaml) else {
+        return Vec::new();
+    };
+    let Some(entries) = doc.get("driver_providers").and_then(|v| v.as_sequence()) else {
+        return Vec::new();
+    };
+
+    entries
+        .iter()
+        .filter_map(|entry| serde_yaml::from_value::<DriverProviderConfig>(entry.clone()).ok())
+        .collect()
+}
+
+/// One event from [`DriverBackend::generate_step_stream`]: either a chunk of
+/// assistant text as it arrives, or the final aggregate -- the same
+/// `(text, tool_calls)` `generate_step` returns -- once the model is done.
+/// Bundling both into one stream, rather than chunks on one channel and the
+/// final result on another, keeps a `BoxStream` the only thing a caller has
+/// to poll.
+pub enum DriverStreamEvent {
+    TextChunk(String),
+    Done { text: String, tool_calls: Vec<DriverFunctionCall> },
+}
+
+pub type DriverChunkStream = futures::stream::BoxStream<'static, Result<DriverStreamEvent, DriverError>>;
+
+/// Drains a [`DriverChunkStream`], discarding intermediate `TextChunk`s, for
+/// callers that only want the final result -- the streaming counterpart of
+/// calling `generate_step` directly.
+pub async fn collect_stream(mut stream: DriverChunkStream) -> Result<(String, Vec<DriverFunctionCall>), DriverError> {
+    use futures::StreamExt;
+    while let Some(event) = stream.next().await {
+        match event? {
+            DriverStreamEvent::TextChunk(_) => {}
+            DriverStreamEvent::Done { text, tool_calls } => return Ok((text, tool_calls)),
+        }
+    }
+    Err(DriverError::Permanent("Stream ended without a Done event".to_string()))
+}
+
+#[async_trait]
+pub trait DriverBackend: Send + Sync {
+    /// Sends `conversation` (a sequence of `{"role": ..., "content": ...}`
+    /// and `{"role": "tool", "tool_call_id": ..., "content": ...}` entries,
+    /// the same shape `driver_loop` and `conversation_log` already use) plus
+    /// the offered `functions` JSON-Schema array, and returns the assistant's
+    /// text together with any function calls it asked for.
+    async fn generate_step(
+        &self,
+        conversation: &[serde_json::Value],
+        functions: &serde_json::Value,
+    ) -> Result<(String, Vec<DriverFunctionCall>), DriverError>;
+
+    /// Streaming counterpart to `generate_step`, for callers that want to
+    /// show live progress (e.g. while waiting on a slow bugcrowd review)
+    /// instead of blocking on the whole reply. The default just awaits
+    /// `generate_step` and emits its result as a single `Done` -- a backend
+    /// that can't stream keeps working unchanged; only `OpenAiBackend`
+    /// overrides this with real incremental chunks, since it's the only one
+    /// routed through a wire API (`codex_core`'s) that actually streams.
+    async fn generate_step_stream(
+        &self,
+        conversation: &[serde_json::Value],
+        functions: &serde_json::Value,
+    ) -> DriverChunkStream {
+        let event = match self.generate_step(conversation, functions).await {
+            Ok((text, tool_calls)) => Ok(DriverStreamEvent::Done { text, tool_calls }),
+            Err(e) => Err(e),
+        };
+        Box::pin(futures::stream::once(async move { event }))
+    }
+}
+
+/// Picks a backend for `model`, honoring `explicit_provider` (`--driver-provider`)
+/// first and falling back to a guess from the model name so existing
+/// `--driver-model o3`-style invocations keep working unchanged.
+///
+/// `driver_providers` is the task config's parsed `driver_providers:` table
+/// (see [`load_driver_providers`]); when `explicit_provider` names an entry
+/// in it, that entry's `base_url`/`env_key`/`wire_api`/`http_headers` are
+/// used verbatim instead of the `openai`/`anthropic`/`cohere` built-ins.
+pub fn backend_for(
+    model: &str,
+    explicit_provider: Option<&str>,
+    driver_providers: &[DriverProviderConfig],
+) -> Box<dyn DriverBackend> {
+    if let Some(name) = explicit_provider {
+        if let Some(config) = driver_providers.iter().find(|p| p.name == name) {
+            return Box::new(OpenAiBackend::from_config(model.to_string(), config.clone()));
+        }
+    }
+
+    let provider = explicit_provider.map(str::to_lowercase).unwrap_or_else(|| {
+        if model.starts_with("claude") {
+            "anthropic".to_string()
+        } else if model.starts_with("command") {
+            "cohere".to_string()
+        } else {
+            "openai".to_string()
+        }
+    });
+
+    match provider.as_str() {
+        "anthropic" | "claude" => Box::new(AnthropicBackend { model: model.to_string() }),
+        "cohere" => Box::new(CohereBackend { model: model.to_string() }),
+        _ => Box::new(OpenAiBackend::openai_default(model.to_string())),
+    }
+}
+
+/// OpenAI-wire chat/function-calling backend, routed through
+/// `codex_core::client::ModelClient` like the rest of codex's own model
+/// calls. Defaults to `api.openai.com`, but carries its own endpoint
+/// details so a `driver_providers` entry can point it anywhere that speaks
+/// the same wire protocol (a local Ollama, an air-gapped proxy, ...).
+pub struct OpenAiBackend {
+    model: String,
+    base_url: String,
+    env_key: Option<String>,
+    wire_api: String,
+    http_headers: Option<HashMap<String, String>>,
+}
+
+impl OpenAiBackend {
+    fn openai_default(model: String) -> Self {
+        Self {
+            model,
+            base_url: "https://api.openai.com/v1".to_string(),
+            env_key: Some("OPENAI_API_KEY".to_string()),
+            wire_api: default_wire_api(),
+            http_headers: None,
+        }
+    }
+
+    fn from_config(model: String, config: DriverProviderConfig) -> Self {
+        Self {
+            model,
+            base_url: config.base_url,
+            env_key: config.env_key,
+            wire_api: config.wire_api,
+            http_headers: config.http_headers,
+        }
+    }
+}
+
+impl OpenAiBackend {
+    /// Builds the `ModelClient`/`Prompt` pair both `generate_step` and
+    /// `generate_step_stream` send -- the only difference between them is
+    /// how they drain the resulting event stream.
+    fn client_and_prompt(
+        &self,
+        conversation: &[serde_json::Value],
+        functions: &serde_json::Value,
+    ) -> (codex_core::client::ModelClient, codex_core::client_common::Prompt) {
+        use codex_core::client::ModelClient;
+        use codex_core::client_common::Prompt;
+        use codex_core::config_types::ReasoningEffort;
+        use codex_core::config_types::ReasoningSummary;
+        use codex_core::model_provider_info::ModelProviderInfo;
+        use codex_core::model_provider_info::WireApi;
+        use codex_core::models::ContentItem;
+        use codex_core::models::ResponseItem;
+
+        let wire_api = match self.wire_api.as_str() {
+            "responses" => WireApi::Responses,
+            _ => WireApi::Chat,
+        };
+
+        let provider = ModelProviderInfo {
+            name: "OpenAI".to_string(),
+            base_url: self.base_url.clone(),
+            env_key: self.env_key.clone(),
+            env_key_instructions: None,
+            wire_api,
+            query_params: None,
+            env_http_headers: None,
+            http_headers: self.http_headers.clone(),
+        };
+
+        let client = ModelClient::new(&self.model, provider, ReasoningEffort::Medium, ReasoningSummary::None);
+
+        let input = conversation
+            .iter()
+            .map(|msg| ResponseItem::Message {
+                role: msg.get("role").and_then(|r| r.as_str()).unwrap_or("user").to_string(),
+                content: vec![ContentItem::InputText {
+                    text: msg.get("content").and_then(|c| c.as_str()).unwrap_or("").to_string(),
+                }],
+            })
+            .collect();
+
+        let mut extra_tools = std::collections::HashMap::new();
+        extra_tools.insert("functions".to_string(), functions.clone());
+
+        let prompt = Prompt {
+            input,
+            prev_id: None,
+            user_instructions: None,
+            store: false,
+            extra_tools,
+        };
+
+        (client, prompt)
+    }
+}
+
+#[async_trait]
+impl DriverBackend for OpenAiBackend {
+    async fn generate_step(
+        &self,
+        conversation: &[serde_json::Value],
+        functions: &serde_json::Value,
+    ) -> Result<(String, Vec<DriverFunctionCall>), DriverError> {
+        use codex_core::client_common::ResponseEvent;
+        use codex_core::models::ContentItem;
+        use codex_core::models::ResponseItem;
+        use futures::StreamExt;
+
+        let (client, prompt) = self.client_and_prompt(conversation, functions);
+
+        let mut response_stream = client
+            .stream(&prompt)
+            .await
+            .map_err(|e| classify_model_client_error(&e))?;
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+
+        while let Some(event) = response_stream.next().await {
+            match event.map_err(|e| classify_model_client_error(&e))? {
+                ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }) => {
+                    for item in content {
+                        if let ContentItem::OutputText { text: chunk } = item {
+                            text.push_str(&chunk);
+                        }
+                    }
+                }
+                ResponseEvent::OutputItemDone(ResponseItem::FunctionCall { id, name, arguments }) => {
+                    let arguments = serde_json::from_str(&arguments).unwrap_or(serde_json::Value::Null);
+                    tool_calls.push(DriverFunctionCall { id, name, arguments });
+                }
+                ResponseEvent::Completed { .. } => break,
+                _ => {}
+            }
+        }
+
+        let text = text.trim().to_string();
+        if text.is_empty() && tool_calls.is_empty() {
+            return Err(DriverError::Permanent("Model returned an empty response".to_string()));
+        }
+        Ok((text, tool_calls))
+    }
+
+    /// Unlike the default, this streams `TextChunk`s as each
+    /// `OutputTextDelta`/`OutputItemDone` arrives off the wire, since
+    /// `codex_core`'s `ModelClient` is the one backend here that actually
+    /// exposes an incremental event stream rather than a single JSON body.
+    async fn generate_step_stream(
+        &self,
+        conversation: &[serde_json::Value],
+        functions: &serde_json::Value,
+    ) -> DriverChunkStream {
+        use codex_core::client_common::ResponseEvent;
+        use codex_core::models::ContentItem;
+        use codex_core::models::ResponseItem;
+        use futures::StreamExt;
+
+        let (client, prompt) = self.client_and_prompt(conversation, functions);
+
+        let response_stream = match client.stream(&prompt).await {
+            Ok(s) => s,
+            Err(e) => return Box::pin(futures::stream::once(async move { Err(classify_model_client_error(&e)) })),
+        };
+
+        struct State<S> {
+            response_stream: S,
+            text: String,
+            tool_calls: Vec<DriverFunctionCall>,
+            finished: bool,
+        }
+
+        let state = State { response_stream, text: String::new(), tool_calls: Vec::new(), finished: false };
+
+        let stream = futures::stream::unfold(state, |mut state| async move {
+            if state.finished {
+                return None;
+            }
+            loop {
+                match state.response_stream.next().await {
+                    None => {
+                        state.finished = true;
+                        let event = Ok(DriverStreamEvent::Done {
+                            text: state.text.trim().to_string(),
+                            tool_calls: std::mem::take(&mut state.tool_calls),
+                        });
+                        return Some((event, state));
+                    }
+                    Some(Err(e)) => {
+                        state.finished = true;
+                        return Some((Err(classify_model_client_error(&e)), state));
+                    }
+                    Some(Ok(ResponseEvent::OutputTextDelta(delta))) => {
+                        state.text.push_str(&delta);
+                        return Some((Ok(DriverStreamEvent::TextChunk(delta)), state));
+                    }
+                    Some(Ok(ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }))) => {
+                        let mut chunk = String::new();
+                        for item in content {
+                            if let ContentItem::OutputText { text } = item {
+                                chunk.push_str(&text);
+                            }
+                        }
+                        if chunk.is_empty() {
+                            continue;
+                        }
+                        state.text.push_str(&chunk);
+                        return Some((Ok(DriverStreamEvent::TextChunk(chunk)), state));
+                    }
+                    Some(Ok(ResponseEvent::OutputItemDone(ResponseItem::FunctionCall { id, name, arguments }))) => {
+                        let arguments = serde_json::from_str(&arguments).unwrap_or(serde_json::Value::Null);
+                        state.tool_calls.push(DriverFunctionCall { id, name, arguments });
+                        continue;
+                    }
+                    Some(Ok(ResponseEvent::Completed { .. })) => {
+                        state.finished = true;
+                        let event = Ok(DriverStreamEvent::Done {
+                            text: state.text.trim().to_string(),
+                            tool_calls: std::mem::take(&mut state.tool_calls),
+                        });
+                        return Some((event, state));
+                    }
+                    Some(Ok(_)) => continue,
+                }
+            }
+        });
+
+        Box::pin(stream)
+    }
+}
+
+/// Anthropic (Claude) Messages API backend. Claude has no equivalent to
+/// codex's own OpenAI-shaped `ModelClient`, so this speaks the Messages API
+/// directly: content is an array of typed blocks (`text`, `tool_use`)
+/// rather than OpenAI's `tool_calls` array, and responses come back the
+/// same way.
+pub struct AnthropicBackend {
+    model: String,
+}
+
+#[async_trait]
+impl DriverBackend for AnthropicBackend {
+    async fn generate_step(
+        &self,
+        conversation: &[serde_json::Value],
+        functions: &serde_json::Value,
+    ) -> Result<(String, Vec<DriverFunctionCall>), DriverError> {
+        let api_key = std::env::var("ANTHROPIC_API_KEY")
+            .map_err(|_| DriverError::Permanent("ANTHROPIC_API_KEY is not set".to_string()))?;
+
+        // `conversation` is OpenAI-shaped (the common format `run_driver_loop`
+        // builds for every backend): an assistant turn carries its function
+        // calls in a top-level `tool_calls` array, and each result comes
+        // back as its own `{role: "tool", tool_call_id, content}` entry.
+        // Anthropic has no equivalent of either -- a tool call is a
+        // `tool_use` content block on the assistant turn, and its result is
+        // a `tool_result` block (carrying that same id as `tool_use_id`) on
+        // the *next* user turn. Translate both rather than flattening to
+        // `{role, content}`, or the Messages API rejects the conversation
+        // once a tool call enters the loop (chunk2-3).
+        let messages: Vec<serde_json::Value> = conversation
+            .iter()
+            .filter(|m| m.get("role").and_then(|r| r.as_str()) != Some("system"))
+            .map(|m| match m.get("role").and_then(|r| r.as_str()) {
+                Some("assistant") => {
+                    let mut blocks = Vec::new();
+                    if let Some(text) = m.get("content").and_then(|c| c.as_str()) {
+                        if !text.is_empty() {
+                            blocks.push(serde_json::json!({ "type": "text", "text": text }));
+                        }
+                    }
+                    for call in m
+                        .get("tool_calls")
+                        .and_then(|c| c.as_array())
+                        .cloned()
+                        .unwrap_or_default()
+                    {
+                        blocks.push(serde_json::json!({
+                            "type": "tool_use",
+                            "id": call.get("id"),
+                            "name": call.get("function").and_then(|f| f.get("name")),
+                            "input": call.get("function").and_then(|f| f.get("arguments")),
+                        }));
+                    }
+                    serde_json::json!({ "role": "assistant", "content": blocks })
+                }
+                Some("tool") => {
+                    serde_json::json!({
+                        "role": "user",
+                        "content": [{
+                            "type": "tool_result",
+                            "tool_use_id": m.get("tool_call_id"),
+                            "content": m.get("content").cloned().unwrap_or(serde_json::json!("")),
+                        }],
+                    })
+                }
+                _ => serde_json::json!({
+                    "role": m.get("role").cloned().unwrap_or(serde_json::json!("user")),
+                    "content": m.get("content").cloned().unwrap_or(serde_json::json!("")),
+                }),
+            })
+            .collect();
+
+        let tools = functions
+            .as_array()
+            .map(|fns| {
+                fns.iter()
+                    .map(|f| {
+                        serde_json::json!({
+                            "name": f.get("name"),
+                            "description": f.get("description"),
+                            "input_schema": f.get("parameters"),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let body = serde_json::json!({
+            "model": self.model,
+            "max_tokens": 4096,
+            "messages": messages,
+            "tools": tools,
+        });
+
+        let client = reqwest::Client::new();
+        let response = client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| classify_reqwest_error(&e))?
+            .error_for_status()
+            .map_err(|e| classify_reqwest_error(&e))?;
+
+        let response_json: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| DriverError::Transient(format!("Failed to decode response body: {e}")))?;
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+
+        for block in response_json
+            .get("content")
+            .and_then(|c| c.as_array())
+            .cloned()
+            .unwrap_or_default()
+        {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => {
+                    text.push_str(block.get("text").and_then(|t| t.as_str()).unwrap_or(""));
+                }
+                Some("tool_use") => {
+                    tool_calls.push(DriverFunctionCall {
+                        id: block.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                        name: block.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                        arguments: block.get("input").cloned().unwrap_or(serde_json::Value::Null),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let text = text.trim().to_string();
+        if text.is_empty() && tool_calls.is_empty() {
+            return Err(DriverError::Permanent("Anthropic returned an empty response".to_string()));
+        }
+        Ok((text, tool_calls))
+    }
+}
+
+/// Cohere Chat API (v2) backend. Tool calls arrive as a `message.tool_calls`
+/// array, each entry nesting `{function: {name, arguments}}` with
+/// `arguments` as a JSON-encoded string -- the same shape OpenAI's
+/// `function.arguments` uses, just one level deeper than Anthropic's
+/// `tool_use` content blocks.
+pub struct CohereBackend {
+    model: String,
+}
+
+#[async_trait]
+impl DriverBackend for CohereBackend {
+    async fn generate_step(
+        &self,
+        conversation: &[serde_json::Value],
+        functions: &serde_json::Value,
+    ) -> Result<(String, Vec<DriverFunctionCall>), DriverError> {
+        let api_key = std::env::var("COHERE_API_KEY")
+            .map_err(|_| DriverError::Permanent("COHERE_API_KEY is not set".to_string()))?;
+
+        let messages: Vec<serde_json::Value> = conversation
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "role": m.get("role").cloned().unwrap_or(serde_json::json!("user")),
+                    "content": m.get("content").cloned().unwrap_or(serde_json::json!("")),
+                })
+            })
+            .collect();
+
+        let tools = functions
+            .as_array()
+            .map(|fns| {
+                fns.iter()
+                    .map(|f| {
+                        serde_json::json!({
+                            "name": f.get("name"),
+                            "description": f.get("description"),
+                            "parameter_definitions": f.get("parameters"),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let body = serde_json::json!({
+            "model": self.model,
+            "messages": messages,
+            "tools": tools,
+        });
+
+        let client = reqwest::Client::new();
+        let response = client
+            .post("https://api.cohere.com/v2/chat")
+            .bearer_auth(api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| classify_reqwest_error(&e))?
+            .error_for_status()
+            .map_err(|e| classify_reqwest_error(&e))?;
+
+        let response_json: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| DriverError::Transient(format!("Failed to decode response body: {e}")))?;
+        let text = response_json
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+            .map(|blocks| {
+                blocks
+                    .iter()
+                    .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("")
+            })
+            .unwrap_or_default();
+
+        let tool_calls = response_json
+            .get("message")
+            .and_then(|m| m.get("tool_calls"))
+            .and_then(|c| c.as_array())
+            .map(|calls| {
+                calls
+                    .iter()
+                    .map(|c| {
+                        let arguments = c
+                            .get("function")
+                            .and_then(|f| f.get("arguments"))
+                            .and_then(|v| v.as_str())
+                            .and_then(|s| serde_json::from_str(s).ok())
+                            .unwrap_or(serde_json::Value::Null);
+                        DriverFunctionCall {
+                            id: c.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                            name: c
+                                .get("function")
+                                .and_then(|f| f.get("name"))
+                                .and_then(|v| v.as_str())
+                                .unwrap_or_default()
+                                .to_string(),
+                            arguments,
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let text = text.trim().to_string();
+        if text.is_empty() && tool_calls.is_empty() {
+            return Err(DriverError::Permanent("Cohere returned an empty response".to_string()));
+        }
+        Ok((text, tool_calls))
+    }
+}
@@ -0,0 +1,185 @@
+//! Multi-step function-calling loop for the external driver model.
+//!
+//! A single-shot driver call yields one free-text instruction that goes
+//! straight to codex. This module lets the driver
+//! chain its own reconnaissance first: it is offered a small set of
+//! driver-side functions and keeps calling them, with results appended to
+//! its own conversation, until it calls `submit_to_codex` (or `max_steps` is
+//! reached, which is treated as a driver error rather than silently falling
+//! back, since a driver that never submits is a bug worth surfacing).
+//!
+//! The driver's conversation (`Vec<serde_json::Value>`) is intentionally
+//! separate from the autonomous session's `conversation_log`: the former is
+//! scratch space for the driver's own reasoning steps, the latter is the
+//! durable record of what was sent to codex and what codex did.
+
+use std::path::Path;
+
+use crate::driver_backend;
+use crate::driver_backend::DriverFunctionCall;
+
+/// JSON Schema-ish declarations for the functions offered to the driver.
+/// Mirrors the shape `extra_tools` already expects elsewhere in this crate.
+fn driver_function_schemas() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "name": "read_session_log",
+            "description": "Read the most recent entries from this session's checkpoint log.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "max_entries": { "type": "integer" }
+                }
+            }
+        },
+        {
+            "name": "summarize_context",
+            "description": "Summarize the readable conversation context accumulated so far.",
+            "parameters": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "set_next_objective",
+            "description": "Record the objective the next codex turn should pursue, without submitting it yet.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "objective": { "type": "string" }
+                },
+                "required": ["objective"]
+            }
+        },
+        {
+            "name": "submit_to_codex",
+            "description": "Finish this driver step and submit the given instruction to codex as the next user turn.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "message": { "type": "string" }
+                },
+                "required": ["message"]
+            }
+        }
+    ])
+}
+
+/// Executes one driver function call locally and returns the text to feed
+/// back as its `role: "tool"` result. `context` is the autonomous session's
+/// readable conversation context (see `main.rs`'s
+/// `build_readable_context`); `session_logs_dir` is where `latest.json` lives.
+fn execute_driver_function(call: &DriverFunctionCall, context: &str, session_logs_dir: &Path) -> String {
+    match call.name.as_str() {
+        "read_session_log" => {
+            let latest_path = session_logs_dir.join("latest.json");
+            match std::fs::read_to_string(&latest_path) {
+                Ok(contents) => {
+                    let max_entries = call
+                        .arguments
+                        .get("max_entries")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(20) as usize;
+                    match serde_json::from_str::<Vec<serde_json::Value>>(&contents) {
+                        Ok(entries) => {
+                            let tail: Vec<&serde_json::Value> =
+                                entries.iter().rev().take(max_entries).collect();
+                            serde_json::to_string_pretty(&tail).unwrap_or_default()
+                        }
+                        Err(e) => format!("Failed to parse {latest_path:?}: {e}"),
+                    }
+                }
+                Err(e) => format!("Failed to read {latest_path:?}: {e}"),
+            }
+        }
+        "summarize_context" => {
+            // A real summarizer would be its own model call; for now this
+            // gives the driver the tail of the context, which is the part
+            // most relevant to deciding the next objective.
+            const TAIL_CHARS: usize = 4000;
+            if context.len() > TAIL_CHARS {
+                // `context[context.len() - TAIL_CHARS..]` would panic if that
+                // byte offset lands mid-codepoint; walk forward to the
+                // nearest char boundary instead.
+                let mut start = context.len() - TAIL_CHARS;
+                while start < context.len() && !context.is_char_boundary(start) {
+                    start += 1;
+                }
+                format!("...{}", &context[start..])
+            } else {
+                context.to_string()
+            }
+        }
+        "set_next_objective" => {
+            let objective = call
+                .arguments
+                .get("objective")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            format!("Objective recorded: {objective}")
+        }
+        other => format!("Unknown driver function: {other}"),
+    }
+}
+
+/// Runs the driver's function-calling loop to completion, returning the
+/// `message` argument of its `submit_to_codex` call.
+pub async fn run_driver_loop(
+    driver_prompt: &str,
+    model: &str,
+    driver_provider: Option<&str>,
+    driver_providers: &[driver_backend::DriverProviderConfig],
+    max_steps: u32,
+    context: &str,
+    session_logs_dir: &Path,
+) -> anyhow::Result<String> {
+    let backend = driver_backend::backend_for(model, driver_provider, driver_providers);
+    let functions = driver_function_schemas();
+    let mut driver_conversation: Vec<serde_json::Value> = vec![serde_json::json!({
+        "role": "user",
+        "content": driver_prompt,
+    })];
+
+    for step in 0..max_steps {
+        let (assistant_text, tool_calls) =
+            backend.generate_step(&driver_conversation, &functions).await?;
+
+        if let Some(submit) = tool_calls.iter().find(|c| c.name == "submit_to_codex") {
+            let message = submit
+                .arguments
+                .get("message")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("submit_to_codex call was missing a `message` argument"))?;
+            return Ok(message.to_string());
+        }
+
+        driver_conversation.push(serde_json::json!({
+            "role": "assistant",
+            "content": assistant_text,
+            "tool_calls": tool_calls.iter().map(|c| serde_json::json!({
+                "id": c.id,
+                "type": "function",
+                "function": { "name": c.name, "arguments": c.arguments },
+            })).collect::<Vec<_>>(),
+        }));
+
+        if tool_calls.is_empty() {
+            // The driver returned plain text with no function call at all;
+            // treat it the same as `submit_to_codex` rather than looping
+            // forever on a driver that never learns the protocol.
+            return Ok(assistant_text);
+        }
+
+        // Invariant: every tool_call above gets exactly one tool result
+        // below before the next model call.
+        for call in &tool_calls {
+            let result = execute_driver_function(call, context, session_logs_dir);
+            driver_conversation.push(serde_json::json!({
+                "role": "tool",
+                "tool_call_id": call.id,
+                "content": result,
+            }));
+        }
+
+        println!("🧭 Driver step {}/{max_steps}: {} tool call(s)", step + 1, tool_calls.len());
+    }
+
+    anyhow::bail!("Driver exceeded max_driver_steps ({max_steps}) without calling submit_to_codex")
+}
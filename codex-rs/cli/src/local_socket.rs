@@ -0,0 +1,46 @@
+//! Local-socket negotiation for MCP tool calls, so a tool that wants its own
+//! interactive/full-screen UI can keep stdio to itself instead of sharing it
+//! with the codex protocol stream. Autonomous mode passes the generated name
+//! to the tool process via `--local-socket <name>`; a tool that doesn't
+//! recognize the flag is expected to ignore it and fall back to stdio, same
+//! as an unrecognized flag would with any other CLI.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+/// A socket (Unix) or named pipe (Windows) generated for one MCP tool call.
+#[derive(Debug, Clone)]
+pub struct LocalSocketName {
+    /// Value to pass as `--local-socket <name>`.
+    pub name: String,
+}
+
+/// Generates a socket name for `tool_name`, unique enough across concurrent
+/// tool calls from this process without needing a shared counter.
+///
+/// On Unix this is a `sun_path`-safe path under `/tmp`: `{tool_name}` and the
+/// current time are hashed together rather than embedded verbatim, keeping
+/// the path short (well under the ~100-byte `sun_path` limit) regardless of
+/// how long `tool_name` is.
+pub fn generate(tool_name: &str) -> LocalSocketName {
+    let mut hasher = DefaultHasher::new();
+    tool_name.hash(&mut hasher);
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .hash(&mut hasher);
+    let hash = hasher.finish();
+    let pid = std::process::id();
+
+    let name = if cfg!(windows) {
+        format!(r"\\.\pipe\artemis.{pid}.{hash:016x}")
+    } else {
+        format!("/tmp/artemis.{pid}.{hash:016x}.sock")
+    };
+
+    LocalSocketName { name }
+}
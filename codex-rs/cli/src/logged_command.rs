@@ -0,0 +1,61 @@
+//! Durable per-command audit records for autonomous mode. `ExecCommandEnd`
+//! handling only keeps a 200-char stdout preview for the console and the raw
+//! output in the conversation log, so anything
+//! auditing a run after the fact has no single place to find a command's
+//! full output. Each [`LoggedCommand`] is written to its own file under
+//! `<session_logs_dir>/commands/` instead.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// One executed command's full forensic record.
+pub struct LoggedCommand {
+    pub command: String,
+    /// stdout followed by stderr. `ExecCommandEnd` hands them over as two
+    /// separate buffers, so the order in which their lines were originally
+    /// interleaved is not preserved.
+    pub combined_output: String,
+    pub exit_code: i32,
+    pub duration: Duration,
+}
+
+impl LoggedCommand {
+    /// Writes this record to `<logs_dir>/commands/{call_id}.log`, creating
+    /// the `commands` directory if needed, and returns the path written.
+    pub fn write(&self, logs_dir: &Path, call_id: &str) -> anyhow::Result<PathBuf> {
+        let commands_dir = logs_dir.join("commands");
+        std::fs::create_dir_all(&commands_dir)?;
+        let path = commands_dir.join(format!("{call_id}.log"));
+        let contents = format!(
+            "command: {}\n{}\nduration: {:.3}s\n\n{}\n",
+            self.command,
+            format_exit_code(self.exit_code),
+            self.duration.as_secs_f64(),
+            self.combined_output
+        );
+        std::fs::write(&path, contents)?;
+        Ok(path)
+    }
+}
+
+/// Renders an exit status the same way on every platform. `std::process`'s
+/// `Display` impl for `ExitStatus` prints `exit status: N` on Unix but
+/// `exit code: N` on Windows; commands executed by the sandboxed exec tool
+/// always come back as a plain `i32`, so there is no platform distinction to
+/// preserve here.
+pub fn format_exit_code(code: i32) -> String {
+    format!("exit code: {code}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_success_and_failure_the_same_way() {
+        assert_eq!(format_exit_code(0), "exit code: 0");
+        assert_eq!(format_exit_code(1), "exit code: 1");
+        assert_eq!(format_exit_code(-1), "exit code: -1");
+    }
+}
@@ -0,0 +1,172 @@
+//! `codex logs` — replay, tail, and summarize the artifacts
+//! `RealtimeLogger` produces (`realtime_context.txt`,
+//! `realtime_conversation.json`, `final_result.json`), turning them into a
+//! queryable, operator-facing tool instead of files you open by hand.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
+use tokio::time::sleep;
+
+#[derive(Debug, clap::Parser)]
+pub struct LogsCommand {
+    #[command(subcommand)]
+    pub cmd: LogsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum LogsSubcommand {
+    /// Reconstruct and print the timeline from `realtime_conversation.json`.
+    Replay {
+        /// Directory passed as `--log-session-dir` to `codex exec`.
+        log_dir: PathBuf,
+    },
+    /// Follow `realtime_context.txt` live, like `tail -f`.
+    Tail {
+        log_dir: PathBuf,
+    },
+    /// Walk many instance subdirectories and tabulate their outcomes.
+    Summarize {
+        /// Directory containing one subdirectory per instance.
+        parent_dir: PathBuf,
+    },
+}
+
+pub async fn run(cmd: LogsCommand) -> anyhow::Result<()> {
+    match cmd.cmd {
+        LogsSubcommand::Replay { log_dir } => run_replay(&log_dir).await,
+        LogsSubcommand::Tail { log_dir } => run_tail(&log_dir).await,
+        LogsSubcommand::Summarize { parent_dir } => run_summarize(&parent_dir).await,
+    }
+}
+
+/// `realtime_conversation.json` is written as NDJSON — one conversation
+/// entry per line — by `FileSink`'s writer task. Each entry is printed as a
+/// timestamped line so an old run can be re-rendered after the fact:
+/// `assistant` entries read as they do in the context file, but every other
+/// entry is printed as `EVENT:` plus its `content` text, which is not the
+/// wording `RealtimeLogger::log_event` uses in the context file for
+/// commands, tool calls or token counts.
+async fn run_replay(log_dir: &Path) -> anyhow::Result<()> {
+    let path = log_dir.join("realtime_conversation.json");
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("Failed to read {path:?}"))?;
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: serde_json::Value = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse conversation entry: {line}"))?;
+        println!("{}", render_entry(&entry));
+    }
+
+    Ok(())
+}
+
+fn render_entry(entry: &serde_json::Value) -> String {
+    let timestamp = entry
+        .get("timestamp")
+        .and_then(|t| t.as_str())
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| "--:--:--".to_string());
+    let role = entry.get("role").and_then(|r| r.as_str()).unwrap_or("system");
+    let content = entry.get("content").and_then(|c| c.as_str()).unwrap_or("");
+
+    match role {
+        "assistant" => format!("[{timestamp}] ASSISTANT: {content}"),
+        "user" => format!("[{timestamp}] USER: {content}"),
+        _ => format!("[{timestamp}] EVENT: {content}"),
+    }
+}
+
+async fn run_tail(log_dir: &Path) -> anyhow::Result<()> {
+    let path = log_dir.join("realtime_context.txt");
+    let mut file = tokio::fs::File::open(&path)
+        .await
+        .with_context(|| format!("Failed to open {path:?}"))?;
+    let mut position = 0u64;
+
+    loop {
+        let metadata = file.metadata().await?;
+        if metadata.len() > position {
+            file.seek(std::io::SeekFrom::Start(position)).await?;
+            let mut buf = String::new();
+            file.read_to_string(&mut buf).await?;
+            print!("{buf}");
+            position = metadata.len();
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+}
+
+async fn run_summarize(parent_dir: &Path) -> anyhow::Result<()> {
+    let mut rows = Vec::new();
+    let mut entries = tokio::fs::read_dir(parent_dir)
+        .await
+        .with_context(|| format!("Failed to read {parent_dir:?}"))?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        if !entry.file_type().await?.is_dir() {
+            continue;
+        }
+        let final_result_path = entry.path().join("final_result.json");
+        let Ok(content) = tokio::fs::read_to_string(&final_result_path).await else {
+            continue;
+        };
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else {
+            continue;
+        };
+
+        let instance_id = value
+            .get("instance_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let status = value
+            .get("status")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let duration_secs = match (
+            value.get("started_at").and_then(|v| v.as_str()),
+            value.get("completed_at").and_then(|v| v.as_str()),
+        ) {
+            (Some(started), Some(completed)) => {
+                match (
+                    chrono::DateTime::parse_from_rfc3339(started),
+                    chrono::DateTime::parse_from_rfc3339(completed),
+                ) {
+                    (Ok(started), Ok(completed)) => {
+                        (completed - started).num_seconds().max(0)
+                    }
+                    _ => 0,
+                }
+            }
+            _ => 0,
+        };
+        let total_tokens = value
+            .get("usage")
+            .and_then(|u| u.get("total_tokens"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+
+        rows.push((instance_id, status, duration_secs, total_tokens));
+    }
+
+    println!(
+        "{:<30} {:<12} {:>10} {:>14}",
+        "INSTANCE_ID", "STATUS", "DURATION_S", "TOTAL_TOKENS"
+    );
+    for (instance_id, status, duration_secs, total_tokens) in rows {
+        println!("{instance_id:<30} {status:<12} {duration_secs:>10} {total_tokens:>14}");
+    }
+
+    Ok(())
+}
@@ -1,3 +1,12 @@
+mod driver_backend;
+mod driver_loop;
+mod conversation_log;
+mod local_socket;
+mod logged_command;
+mod logs;
+mod review_loop;
+mod service;
+
 use clap::Parser;
 use codex_cli::LandlockCommand;
 use codex_cli::SeatbeltCommand;
@@ -6,9 +15,11 @@ use codex_cli::proto;
 use codex_common::CliConfigOverrides;
 use codex_exec::Cli as ExecCli;
 use codex_tui::Cli as TuiCli;
+use std::path::Path;
 use std::path::PathBuf;
 use anyhow::Context;
 
+use crate::logs::LogsCommand;
 use crate::proto::ProtoCli;
 
 /// Codex CLI
@@ -52,8 +63,16 @@ enum Subcommand {
     #[clap(visible_alias = "p")]
     Proto(ProtoCli),
 
+    /// Resume an autonomous session that was interrupted (crash, timeout,
+    /// Ctrl-C) from its last checkpoint.
+    Resume(ResumeCommand),
+
     /// Internal debugging commands.
     Debug(DebugArgs),
+
+    /// Replay, tail, or summarize artifacts written by `codex exec`'s
+    /// realtime logger.
+    Logs(LogsCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -78,6 +97,7 @@ struct LoginCommand {
 }
 
 #[derive(Debug, Parser)]
+#[clap(subcommand_negates_reqs = true)]
 struct AutonomousCommand {
     /// Path to the configuration YAML file.
     #[clap(long, short = 'f', value_name = "FILE")]
@@ -91,10 +111,99 @@ struct AutonomousCommand {
     #[clap(long, short = 'm', default_value = "o3")]
     driver_model: String,
 
+    /// Which provider's request/response shape to use for the driver model
+    /// (`openai`, `anthropic`, `cohere`). Defaults to a guess from
+    /// `--driver-model`'s name.
+    #[clap(long)]
+    driver_provider: Option<String>,
+
     /// Enable full-auto mode (skip all approvals and use workspace-write sandbox).
     #[clap(long = "full-auto")]
     full_auto: bool,
 
+    /// Maximum number of function-calling steps the driver model may take
+    /// (reading the session log, summarizing context, setting an objective)
+    /// before it must call `submit_to_codex`. Exceeding this is treated as a
+    /// driver error rather than a silent fallback.
+    #[clap(long, default_value_t = 8)]
+    max_driver_steps: u32,
+
+    /// Detach this run as a background service (launchd on macOS, a systemd
+    /// `--user` unit on Linux) instead of keeping it attached to this
+    /// terminal. Use `artemis autonomous log` to stream its output
+    /// afterwards.
+    #[clap(long)]
+    service: bool,
+
+    /// Session logs directory to reuse instead of generating a new
+    /// timestamped one. Set internally when `--service` relaunches this
+    /// same command inside the installed service, so the service writes to
+    /// the directory its installer already reported.
+    #[clap(long = "session-log-dir", hide = true)]
+    session_log_dir: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    action: Option<AutonomousAction>,
+
+    #[clap(flatten)]
+    config_overrides: CliConfigOverrides,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum AutonomousAction {
+    /// Stream a session's output -- running in the foreground or as a
+    /// `--service` -- like `tail -f`.
+    Log(AutonomousLogArgs),
+}
+
+#[derive(Debug, Parser)]
+struct AutonomousLogArgs {
+    /// Session logs directory, e.g. `./logs/autonomous_session_1700000000`.
+    log_dir: PathBuf,
+
+    /// Delegate to `journalctl --user -u <unit> -f` instead of polling
+    /// `service.log`. Only meaningful for a Linux `--service` run.
+    #[clap(long)]
+    journal: bool,
+}
+
+#[derive(Debug, Parser)]
+struct ResumeCommand {
+    /// Session logs directory of the interrupted run, e.g.
+    /// `./logs/autonomous_session_1700000000`.
+    #[clap(long = "log-dir", short = 'l', value_name = "DIR")]
+    log_dir: PathBuf,
+
+    /// Path to the same configuration YAML file the original session used.
+    #[clap(long, short = 'f', value_name = "FILE")]
+    config_file: PathBuf,
+
+    /// Minutes to run for, counted from the original session's start, not
+    /// from now. Defaults to the original session's `--duration`, so the
+    /// resumed run stops when the original budget would have. Pass a larger
+    /// value to extend the budget instead.
+    #[clap(long, short = 'd')]
+    duration: Option<u64>,
+
+    /// Model to use for the external LLM driver.
+    #[clap(long, short = 'm', default_value = "o3")]
+    driver_model: String,
+
+    /// Which provider's request/response shape to use for the driver model
+    /// (`openai`, `anthropic`, `cohere`). Defaults to a guess from
+    /// `--driver-model`'s name.
+    #[clap(long)]
+    driver_provider: Option<String>,
+
+    /// Enable full-auto mode (skip all approvals and use workspace-write sandbox).
+    #[clap(long = "full-auto")]
+    full_auto: bool,
+
+    /// Maximum number of function-calling steps the driver model may take
+    /// before it must call `submit_to_codex`.
+    #[clap(long, default_value_t = 8)]
+    max_driver_steps: u32,
+
     #[clap(flatten)]
     config_overrides: CliConfigOverrides,
 }
@@ -122,10 +231,15 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
         Some(Subcommand::Mcp) => {
             codex_mcp_server::run_main(codex_linux_sandbox_exe).await?;
         }
-        Some(Subcommand::Autonomous(mut autonomous_cli)) => {
-            prepend_config_flags(&mut autonomous_cli.config_overrides, cli.config_overrides);
-            run_autonomous_mode(autonomous_cli, codex_linux_sandbox_exe).await?;
-        }
+        Some(Subcommand::Autonomous(mut autonomous_cli)) => match autonomous_cli.action.take() {
+            Some(AutonomousAction::Log(log_args)) => {
+                service::tail_log(&log_args.log_dir, log_args.journal).await?;
+            }
+            None => {
+                prepend_config_flags(&mut autonomous_cli.config_overrides, cli.config_overrides);
+                run_autonomous_mode(autonomous_cli, codex_linux_sandbox_exe).await?;
+            }
+        },
         Some(Subcommand::Login(mut login_cli)) => {
             prepend_config_flags(&mut login_cli.config_overrides, cli.config_overrides);
             run_login_with_chatgpt(login_cli.config_overrides).await;
@@ -134,6 +248,10 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut proto_cli.config_overrides, cli.config_overrides);
             proto::run_main(proto_cli).await?;
         }
+        Some(Subcommand::Resume(mut resume_cli)) => {
+            prepend_config_flags(&mut resume_cli.config_overrides, cli.config_overrides);
+            run_resume_mode(resume_cli, codex_linux_sandbox_exe).await?;
+        }
         Some(Subcommand::Debug(debug_args)) => match debug_args.cmd {
             DebugCommand::Seatbelt(mut seatbelt_cli) => {
                 prepend_config_flags(&mut seatbelt_cli.config_overrides, cli.config_overrides);
@@ -152,130 +270,186 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 .await?;
             }
         },
+        Some(Subcommand::Logs(logs_cli)) => {
+            logs::run(logs_cli).await?;
+        }
     }
 
     Ok(())
 }
 
-async fn run_autonomous_mode(
-    autonomous_cli: AutonomousCommand,
-    _codex_linux_sandbox_exe: Option<PathBuf>,
-) -> anyhow::Result<()> {
-    use codex_core::config::Config;
-    use codex_core::codex_wrapper::init_codex;
-    use codex_core::protocol::{Op, InputItem};
-    use std::time::{Duration, Instant};
-    use tokio::time::sleep;
-    
-    println!("🚀 Starting autonomous mode...");
-    println!("📁 Config file: {:?}", autonomous_cli.config_file);
-    println!("⏰ Duration: {} minutes", autonomous_cli.duration);
-    println!("🤖 Driver model: {}", autonomous_cli.driver_model);
-    
-    // Load config file
-    let config_content = std::fs::read_to_string(&autonomous_cli.config_file)
-        .with_context(|| format!("Failed to read config file: {:?}", autonomous_cli.config_file))?;
-    
-    // Load prompt templates from core directory
+/// Prompt templates and task config shared by every iteration of the main
+/// autonomous loop, loaded once up front by both a fresh run and a resumed
+/// one.
+struct LoopTemplates {
+    config_content: String,
+    driver_providers: Vec<driver_backend::DriverProviderConfig>,
+    initial_prompt_template: String,
+    continuation_prompt_template: String,
+    approval_prompt_template: String,
+    bugcrowd_approval_prompt_template: String,
+}
+
+fn load_loop_templates(config_file: &Path) -> anyhow::Result<LoopTemplates> {
+    let config_content = std::fs::read_to_string(config_file)
+        .with_context(|| format!("Failed to read config file: {config_file:?}"))?;
+    let driver_providers = driver_backend::load_driver_providers(&config_content);
+
     let core_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .parent()
         .unwrap()
         .join("core");
-    
-    let initial_prompt_file = core_dir.join("initial_prompt.txt");
-    let continuation_prompt_file = core_dir.join("continuation_prompt.txt");
-    let approval_prompt_file = core_dir.join("approval_prompt.txt");
-    let bugcrowd_approval_prompt_file = core_dir.join("bugcrowd_approval_prompt.txt");
-    
-    let initial_prompt_template = std::fs::read_to_string(&initial_prompt_file)
-        .with_context(|| format!("Failed to read initial prompt file: {:?}", initial_prompt_file))?;
-    
-    let continuation_prompt_template = std::fs::read_to_string(&continuation_prompt_file)
-        .with_context(|| format!("Failed to read continuation prompt file: {:?}", continuation_prompt_file))?;
-    
-    let approval_prompt_template = std::fs::read_to_string(&approval_prompt_file)
-        .with_context(|| format!("Failed to read approval prompt file: {:?}", approval_prompt_file))?;
-    
-    let bugcrowd_approval_prompt_template = std::fs::read_to_string(&bugcrowd_approval_prompt_file)
-        .with_context(|| format!("Failed to read bugcrowd approval prompt file: {:?}", bugcrowd_approval_prompt_file))?;
-    
+
+    let initial_prompt_template = std::fs::read_to_string(core_dir.join("initial_prompt.txt"))
+        .with_context(|| "Failed to read initial prompt file")?;
+    let continuation_prompt_template =
+        std::fs::read_to_string(core_dir.join("continuation_prompt.txt"))
+            .with_context(|| "Failed to read continuation prompt file")?;
+    let approval_prompt_template = std::fs::read_to_string(core_dir.join("approval_prompt.txt"))
+        .with_context(|| "Failed to read approval prompt file")?;
+    let bugcrowd_approval_prompt_template =
+        std::fs::read_to_string(core_dir.join("bugcrowd_approval_prompt.txt"))
+            .with_context(|| "Failed to read bugcrowd approval prompt file")?;
+
     println!("📋 Task config loaded");
     println!("📝 Prompt templates loaded");
-    
-    // Create codex config with overrides, applying full-auto settings if enabled
-    let mut config_overrides = codex_core::config::ConfigOverrides::default();
-    if autonomous_cli.full_auto {
-        config_overrides.approval_policy = Some(codex_core::protocol::AskForApproval::OnFailure);
-        config_overrides.sandbox_policy = Some(codex_core::protocol::SandboxPolicy::new_workspace_write_policy());
+
+    Ok(LoopTemplates {
+        config_content,
+        driver_providers,
+        initial_prompt_template,
+        continuation_prompt_template,
+        approval_prompt_template,
+        bugcrowd_approval_prompt_template,
+    })
+}
+
+fn build_codex_config(
+    full_auto: bool,
+    config_overrides: CliConfigOverrides,
+) -> anyhow::Result<codex_core::config::Config> {
+    use codex_core::config::Config;
+
+    let mut overrides = codex_core::config::ConfigOverrides::default();
+    if full_auto {
+        overrides.approval_policy = Some(codex_core::protocol::AskForApproval::OnFailure);
+        overrides.sandbox_policy =
+            Some(codex_core::protocol::SandboxPolicy::new_workspace_write_policy());
     }
-    
-    let config = Config::load_with_cli_overrides(
-        autonomous_cli.config_overrides.parse_overrides()
-            .map_err(anyhow::Error::msg)?,
-        config_overrides,
+
+    Config::load_with_cli_overrides(
+        config_overrides.parse_overrides().map_err(anyhow::Error::msg)?,
+        overrides,
     )
-    .with_context(|| "Failed to load codex config")?;
-    
-    // Initialize codex session
-    let (codex, _init_event, _ctrl_c) = init_codex(config.clone()).await?;
-    println!("✅ Codex session initialized");
-    
-    // Initialize context accumulator and conversation log
-    let mut context = String::new();
-    let mut conversation_log = Vec::new();
-    let mut iteration = 0;
-    let start_time = Instant::now();
-    let duration = Duration::from_secs(autonomous_cli.duration * 60);
-    
-    // Create session-specific logs directory with timestamp
-    let session_timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    let session_logs_dir = PathBuf::from("./logs").join(format!("autonomous_session_{}", session_timestamp));
-    std::fs::create_dir_all(&session_logs_dir)
-        .with_context(|| format!("Failed to create session logs directory: {:?}", session_logs_dir))?;
-    
-    println!("📁 Session logs directory: {:?}", session_logs_dir);
-    
-    // Load codex system prompt from prompt.md
-    let prompt_md_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .parent()
-        .unwrap()
-        .join("core")
-        .join("prompt.md");
-    let system_prompt = std::fs::read_to_string(&prompt_md_path)
-        .with_context(|| format!("Failed to read system prompt from: {:?}", prompt_md_path))?;
-    
-    // Add system message to conversation log
-    conversation_log.push(serde_json::json!({
-        "role": "system",
-        "content": system_prompt
-    }));
-    
-    // Function to save checkpoint log files
-    let save_checkpoint = |log: &Vec<serde_json::Value>, iteration_num: u32| {
+    .with_context(|| "Failed to load codex config")
+}
+
+/// Renders `conversation_log` the way the driver prompt templates expect to
+/// read it back as `{context}`. Shared by the main loop (which rebuilds it
+/// after every iteration) and `run_resume_mode` (which rebuilds it once from
+/// the persisted `latest.json`).
+fn build_readable_context(conversation_log: &[serde_json::Value]) -> String {
+    let mut readable_context = String::new();
+    for msg in conversation_log {
+        match msg.get("role").and_then(|r| r.as_str()) {
+            Some("system") => {
+                readable_context.push_str(&format!(
+                    "SYSTEM: {}\n\n",
+                    msg.get("content").and_then(|c| c.as_str()).unwrap_or("")
+                ));
+            }
+            Some("user") => {
+                readable_context.push_str(&format!(
+                    "USER: {}\n\n",
+                    msg.get("content").and_then(|c| c.as_str()).unwrap_or("")
+                ));
+            }
+            Some("assistant") => {
+                if let Some(reasoning) = msg.get("reasoning") {
+                    readable_context.push_str(&format!(
+                        "ASSISTANT_REASONING: {}\n\n",
+                        reasoning.as_str().unwrap_or("")
+                    ));
+                } else if let Some(tool_calls) = msg.get("tool_calls") {
+                    readable_context.push_str(&format!(
+                        "ASSISTANT_TOOL_CALLS: {}\n\n",
+                        serde_json::to_string_pretty(tool_calls).unwrap_or_default()
+                    ));
+                } else {
+                    readable_context.push_str(&format!(
+                        "ASSISTANT: {}\n\n",
+                        msg.get("content").and_then(|c| c.as_str()).unwrap_or("")
+                    ));
+                }
+            }
+            Some("tool") => {
+                readable_context.push_str(&format!(
+                    "TOOL_RESPONSE: {}\n\n",
+                    msg.get("content").and_then(|c| c.as_str()).unwrap_or("")
+                ));
+            }
+            _ => {
+                // Skip unknown roles
+            }
+        }
+    }
+    readable_context
+}
+
+/// Starting point for [`run_loop`], covering both a brand-new session
+/// (`iteration: 0`, empty `conversation_log`/`context`, `start_time: now`)
+/// and a resumed one (rehydrated from a prior session's checkpoint).
+struct LoopState {
+    conversation_log: Vec<serde_json::Value>,
+    iteration: u32,
+    context: String,
+    start_time: std::time::Instant,
+    session_timestamp: u64,
+}
+
+/// Runs iterations of the autonomous loop until `duration` (measured from
+/// `state.start_time`, not from when this function was called) elapses,
+/// checkpointing after every iteration. `session_logs_dir` must already
+/// exist.
+#[allow(clippy::too_many_arguments)]
+async fn run_loop(
+    codex: &codex_core::Codex,
+    templates: &LoopTemplates,
+    driver_model: &str,
+    driver_provider: Option<&str>,
+    full_auto: bool,
+    max_driver_steps: u32,
+    session_logs_dir: &Path,
+    duration_minutes: u64,
+    mut state: LoopState,
+) -> anyhow::Result<()> {
+    use codex_core::protocol::{InputItem, Op};
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    let duration = Duration::from_secs(duration_minutes * 60);
+    let session_timestamp = state.session_timestamp;
+
+    let save_checkpoint = |log: &Vec<serde_json::Value>, iteration_num: u32, start_time: std::time::Instant| {
         let log_json = serde_json::to_string_pretty(log).unwrap_or_else(|_| "[]".to_string());
-        
-        // Save numbered checkpoint
-        let checkpoint_path = session_logs_dir.join(format!("iteration_{:03}.json", iteration_num));
+
+        let checkpoint_path = session_logs_dir.join(format!("iteration_{iteration_num:03}.json"));
         if let Err(e) = std::fs::write(&checkpoint_path, &log_json) {
-            eprintln!("❌ Failed to save checkpoint {}: {}", iteration_num, e);
+            eprintln!("❌ Failed to save checkpoint {iteration_num}: {e}");
         } else {
-            println!("📝 Checkpoint {} saved to: {:?}", iteration_num, checkpoint_path);
+            println!("📝 Checkpoint {iteration_num} saved to: {checkpoint_path:?}");
         }
-        
-        // Also save as latest.json for easy access
+
         let latest_path = session_logs_dir.join("latest.json");
         if let Err(e) = std::fs::write(&latest_path, &log_json) {
-            eprintln!("❌ Failed to save latest.json: {}", e);
+            eprintln!("❌ Failed to save latest.json: {e}");
         }
-        
-        // Save session metadata
+
         let metadata = serde_json::json!({
             "session_start": session_timestamp,
             "current_iteration": iteration_num,
             "elapsed_seconds": start_time.elapsed().as_secs(),
+            "duration_minutes": duration_minutes,
             "last_updated": std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
@@ -283,149 +457,346 @@ async fn run_autonomous_mode(
         });
         let metadata_path = session_logs_dir.join("session_info.json");
         if let Err(e) = std::fs::write(&metadata_path, serde_json::to_string_pretty(&metadata).unwrap_or_default()) {
-            eprintln!("❌ Failed to save session metadata: {}", e);
+            eprintln!("❌ Failed to save session metadata: {e}");
         }
     };
-    
-    // Save initial checkpoint with system message
-    save_checkpoint(&conversation_log, 0);
-    println!("🚀 Session {} started with {} minute duration", session_timestamp, autonomous_cli.duration);
-    
-    // Main autonomous loop with error handling
+
+    save_checkpoint(&state.conversation_log, state.iteration, state.start_time);
+
     let loop_result = async {
-        while start_time.elapsed() < duration {
-            iteration += 1;
-            println!("\n🔄 Iteration {} ({}s elapsed)", iteration, start_time.elapsed().as_secs());
-        
-            // Determine which prompt template to use
-            let prompt_template = if iteration == 1 {
-                &initial_prompt_template
+        while state.start_time.elapsed() < duration {
+            state.iteration += 1;
+            println!(
+                "\n🔄 Iteration {} ({}s elapsed)",
+                state.iteration,
+                state.start_time.elapsed().as_secs()
+            );
+
+            let prompt_template = if state.iteration == 1 {
+                &templates.initial_prompt_template
             } else {
-                &continuation_prompt_template
+                &templates.continuation_prompt_template
             };
-        
-            // Inject config and context into prompt template
+
             let driver_prompt = inject_template_variables(
                 prompt_template,
-                &config_content,
-                &context,
+                &templates.config_content,
+                &state.context,
             );
-        
-            // Generate user prompt using external LLM
-            let user_prompt = generate_user_prompt(
+
+            // Let the driver chain its own reconnaissance (reading the
+            // session log, summarizing context, setting an objective)
+            // before it commits to the next codex instruction.
+            let user_prompt = driver_loop::run_driver_loop(
                 &driver_prompt,
-                &autonomous_cli.driver_model,
+                driver_model,
+                driver_provider,
+                &templates.driver_providers,
+                max_driver_steps,
+                &state.context,
+                session_logs_dir,
             ).await?;
-            
+
             println!("💭 Generated user prompt: {}", user_prompt);
-            
-            // Add user message to conversation log
-            conversation_log.push(serde_json::json!({
+
+            state.conversation_log.push(serde_json::json!({
                 "role": "user",
                 "content": user_prompt
             }));
-        
-            // Submit to codex
+
             let input_items = vec![InputItem::Text { text: user_prompt.clone() }];
             let submission_id = codex.submit(Op::UserInput { items: input_items }).await?;
-            
-            // Collect codex response and tool calls
-            let (codex_response, tool_calls, reasoning, tool_responses) = collect_codex_response_with_tools(&codex, &submission_id, autonomous_cli.full_auto, &autonomous_cli.driver_model, &approval_prompt_template, &bugcrowd_approval_prompt_template).await?;
-            
+
+            let (codex_response, tool_calls, reasoning, tool_responses) = collect_codex_response_with_tools(
+                codex,
+                &submission_id,
+                full_auto,
+                driver_model,
+                driver_provider,
+                &templates.driver_providers,
+                &templates.approval_prompt_template,
+                &templates.bugcrowd_approval_prompt_template,
+                session_logs_dir,
+            ).await?;
+
             println!("🤖 Codex response collected");
-            
+
             // Add events in correct chronological order:
-            
-            // 1. Assistant reasoning (if present)
+
             if let Some(reasoning_text) = reasoning {
-                conversation_log.push(serde_json::json!({
+                state.conversation_log.push(serde_json::json!({
                     "role": "assistant",
                     "content": "",
                     "reasoning": reasoning_text
                 }));
             }
-            
-            // 2. Assistant tool calls (if any)
+
             if !tool_calls.is_empty() {
-                conversation_log.push(serde_json::json!({
-                    "role": "assistant", 
+                state.conversation_log.push(serde_json::json!({
+                    "role": "assistant",
                     "content": "",
                     "tool_calls": tool_calls
                 }));
             }
-            
-            // 3. Tool responses
+
             for tool_response in tool_responses {
-                conversation_log.push(tool_response);
+                state.conversation_log.push(tool_response);
             }
-            
-            // 4. Final assistant response
-            conversation_log.push(serde_json::json!({
+
+            state.conversation_log.push(serde_json::json!({
                 "role": "assistant",
                 "content": codex_response
             }));
-            
-            // Build readable conversation context
-            let mut readable_context = String::new();
-            for msg in &conversation_log {
-                match msg.get("role").and_then(|r| r.as_str()) {
-                    Some("system") => {
-                        readable_context.push_str(&format!("SYSTEM: {}\n\n", 
-                            msg.get("content").and_then(|c| c.as_str()).unwrap_or("")));
-                    }
-                    Some("user") => {
-                        readable_context.push_str(&format!("USER: {}\n\n", 
-                            msg.get("content").and_then(|c| c.as_str()).unwrap_or("")));
-                    }
-                    Some("assistant") => {
-                        if let Some(reasoning) = msg.get("reasoning") {
-                            readable_context.push_str(&format!("ASSISTANT_REASONING: {}\n\n", 
-                                reasoning.as_str().unwrap_or("")));
-                        } else if let Some(tool_calls) = msg.get("tool_calls") {
-                            readable_context.push_str(&format!("ASSISTANT_TOOL_CALLS: {}\n\n", 
-                                serde_json::to_string_pretty(tool_calls).unwrap_or_default()));
-                        } else {
-                            readable_context.push_str(&format!("ASSISTANT: {}\n\n", 
-                                msg.get("content").and_then(|c| c.as_str()).unwrap_or("")));
-                        }
-                    }
-                    Some("tool") => {
-                        readable_context.push_str(&format!("TOOL_RESPONSE: {}\n\n", 
-                            msg.get("content").and_then(|c| c.as_str()).unwrap_or("")));
-                    }
-                    _ => {
-                        // Skip unknown roles
-                    }
-                }
-            }
-            context = readable_context;
-            
-            // Save checkpoint after each iteration
-            save_checkpoint(&conversation_log, iteration as u32);
-        
-            // Wait before next iteration
+
+            state.context = build_readable_context(&state.conversation_log);
+
+            save_checkpoint(&state.conversation_log, state.iteration, state.start_time);
+
             sleep(Duration::from_secs(10)).await;
         }
-        
-        println!("✅ Autonomous mode completed after {} iterations", iteration);
+
+        println!("✅ Autonomous mode completed after {} iterations", state.iteration);
         Ok::<(), anyhow::Error>(())
     }.await;
-    
-    // Save final checkpoint regardless of how we exit
-    save_checkpoint(&conversation_log, iteration as u32);
-    println!("🏁 Final checkpoint saved for session {}", session_timestamp);
-    
-    // Return the result
+
+    save_checkpoint(&state.conversation_log, state.iteration, state.start_time);
+    println!("🏁 Final checkpoint saved for session {}", state.session_timestamp);
+
     loop_result
 }
 
-async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission_id: &str, _full_auto: bool, driver_model: &str, approval_prompt_template: &str, bugcrowd_approval_prompt_template: &str) -> anyhow::Result<(String, Vec<serde_json::Value>, Option<String>, Vec<serde_json::Value>)> {
+/// Rebuilds the `autonomous ...` argv the installed service should run:
+/// the same invocation that requested `--service`, minus `--service` itself
+/// (so the service process just runs the loop in its own foreground) and
+/// with `--session-log-dir` pinned to the directory the installer already
+/// created and reported to the caller.
+fn autonomous_service_args(cli: &AutonomousCommand, session_logs_dir: &Path) -> Vec<String> {
+    let mut args = vec![
+        "autonomous".to_string(),
+        "--config-file".to_string(),
+        cli.config_file.display().to_string(),
+        "--duration".to_string(),
+        cli.duration.to_string(),
+        "--driver-model".to_string(),
+        cli.driver_model.clone(),
+        "--max-driver-steps".to_string(),
+        cli.max_driver_steps.to_string(),
+        "--session-log-dir".to_string(),
+        session_logs_dir.display().to_string(),
+    ];
+    if let Some(provider) = &cli.driver_provider {
+        args.push("--driver-provider".to_string());
+        args.push(provider.clone());
+    }
+    if cli.full_auto {
+        args.push("--full-auto".to_string());
+    }
+    args
+}
+
+async fn run_autonomous_mode(
+    autonomous_cli: AutonomousCommand,
+    _codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    use codex_core::codex_wrapper::init_codex;
+    use std::time::Instant;
+
+    println!("🚀 Starting autonomous mode...");
+    println!("📁 Config file: {:?}", autonomous_cli.config_file);
+    println!("⏰ Duration: {} minutes", autonomous_cli.duration);
+    println!("🤖 Driver model: {}", autonomous_cli.driver_model);
+
+    let templates = load_loop_templates(&autonomous_cli.config_file)?;
+
+    let (session_timestamp, session_logs_dir) = match &autonomous_cli.session_log_dir {
+        Some(dir) => {
+            let timestamp = dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix("autonomous_session_"))
+                .and_then(|n| n.parse().ok())
+                .unwrap_or_else(|| std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs());
+            (timestamp, dir.clone())
+        }
+        None => {
+            let timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            (timestamp, PathBuf::from("./logs").join(format!("autonomous_session_{timestamp}")))
+        }
+    };
+    std::fs::create_dir_all(&session_logs_dir)
+        .with_context(|| format!("Failed to create session logs directory: {session_logs_dir:?}"))?;
+    println!("📁 Session logs directory: {session_logs_dir:?}");
+
+    if autonomous_cli.service {
+        let service_args = autonomous_service_args(&autonomous_cli, &session_logs_dir);
+        let log_path = service::install_and_start(session_timestamp, &session_logs_dir, &service_args)?;
+        println!("🛠️  Installed as a background service, logging to {log_path:?}");
+        println!(
+            "   Stream it with: artemis autonomous log {}",
+            session_logs_dir.display()
+        );
+        return Ok(());
+    }
+
+    let config = build_codex_config(autonomous_cli.full_auto, autonomous_cli.config_overrides)?;
+
+    let (codex, _init_event, _ctrl_c) = init_codex(config.clone()).await?;
+    println!("✅ Codex session initialized");
+
+    let prompt_md_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("core")
+        .join("prompt.md");
+    let system_prompt = std::fs::read_to_string(&prompt_md_path)
+        .with_context(|| format!("Failed to read system prompt from: {prompt_md_path:?}"))?;
+
+    let conversation_log = vec![serde_json::json!({
+        "role": "system",
+        "content": system_prompt
+    })];
+
+    println!("🚀 Session {session_timestamp} started with {} minute duration", autonomous_cli.duration);
+
+    run_loop(
+        &codex,
+        &templates,
+        &autonomous_cli.driver_model,
+        autonomous_cli.driver_provider.as_deref(),
+        autonomous_cli.full_auto,
+        autonomous_cli.max_driver_steps,
+        &session_logs_dir,
+        autonomous_cli.duration,
+        LoopState {
+            conversation_log,
+            iteration: 0,
+            context: String::new(),
+            start_time: Instant::now(),
+            session_timestamp,
+        },
+    )
+    .await
+}
+
+/// Persisted `session_info.json` shape written by [`run_loop`]'s
+/// `save_checkpoint`.
+#[derive(serde::Deserialize)]
+struct SessionInfo {
+    session_start: u64,
+    current_iteration: u32,
+    elapsed_seconds: u64,
+    duration_minutes: u64,
+}
+
+async fn run_resume_mode(
+    resume_cli: ResumeCommand,
+    _codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    use codex_core::codex_wrapper::init_codex;
     use codex_core::protocol::EventMsg;
+    use codex_core::protocol::InputItem;
+    use codex_core::protocol::Op;
+    use std::time::{Duration, Instant};
+
+    println!("🔁 Resuming autonomous session from {:?}", resume_cli.log_dir);
+
+    let templates = load_loop_templates(&resume_cli.config_file)?;
+    let config = build_codex_config(resume_cli.full_auto, resume_cli.config_overrides)?;
+
+    let (codex, _init_event, _ctrl_c) = init_codex(config.clone()).await?;
+    println!("✅ Codex session (re)initialized");
+
+    let latest_path = resume_cli.log_dir.join("latest.json");
+    let conversation_log: Vec<serde_json::Value> = serde_json::from_str(
+        &std::fs::read_to_string(&latest_path)
+            .with_context(|| format!("Failed to read {latest_path:?}"))?,
+    )
+    .with_context(|| format!("Failed to parse {latest_path:?} as a conversation log"))?;
+
+    let session_info_path = resume_cli.log_dir.join("session_info.json");
+    let session_info: SessionInfo = serde_json::from_str(
+        &std::fs::read_to_string(&session_info_path)
+            .with_context(|| format!("Failed to read {session_info_path:?}"))?,
+    )
+    .with_context(|| format!("Failed to parse {session_info_path:?}"))?;
+
+    let context = build_readable_context(&conversation_log);
+    // `start_time` is backdated by the persisted elapsed time so the
+    // resumed loop's `start_time.elapsed() < duration` check still measures
+    // against the original session's start, not against now.
+    let start_time = Instant::now()
+        .checked_sub(Duration::from_secs(session_info.elapsed_seconds))
+        .unwrap_or_else(Instant::now);
+    let duration_minutes = resume_cli.duration.unwrap_or(session_info.duration_minutes);
+
+    println!(
+        "📋 Rehydrated {} messages, resuming at iteration {} ({}s already elapsed of {} minute budget)",
+        conversation_log.len(),
+        session_info.current_iteration,
+        session_info.elapsed_seconds,
+        duration_minutes,
+    );
+
+    // `init_codex` above starts a brand-new session with no memory of the
+    // interrupted run -- the freshly built `codex` object has never seen
+    // `conversation_log`. Replay it as one priming turn, the same
+    // submit-and-drain-until-`TaskComplete` pattern `run_main` (exec crate)
+    // uses to inject the initial images, so the resumed session actually has
+    // the prior conversation in context before the loop submits a new turn.
+    println!("⏪ Replaying persisted conversation into the resumed session...");
+    let replay_prompt = format!(
+        "The following is the persisted conversation history from a Codex session that was \
+         interrupted and is now being resumed. Treat it as context only -- it has already \
+         happened and should not be re-executed:\n\n{context}"
+    );
+    let replay_items = vec![InputItem::Text { text: replay_prompt }];
+    let replay_submission_id = codex.submit(Op::UserInput { items: replay_items }).await?;
+    loop {
+        match codex.next_event().await {
+            Ok(event) => {
+                if event.id == replay_submission_id && matches!(event.msg, EventMsg::TaskComplete(_)) {
+                    break;
+                }
+            }
+            Err(e) => return Err(anyhow::anyhow!("Error receiving event during replay: {e}")),
+        }
+    }
+    println!("✅ Replay complete");
+
+    run_loop(
+        &codex,
+        &templates,
+        &resume_cli.driver_model,
+        resume_cli.driver_provider.as_deref(),
+        resume_cli.full_auto,
+        resume_cli.max_driver_steps,
+        &resume_cli.log_dir,
+        duration_minutes,
+        LoopState {
+            conversation_log,
+            iteration: session_info.current_iteration,
+            context,
+            start_time,
+            session_timestamp: session_info.session_start,
+        },
+    )
+    .await
+}
+
+async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission_id: &str, _full_auto: bool, driver_model: &str, driver_provider: Option<&str>, driver_providers: &[driver_backend::DriverProviderConfig], approval_prompt_template: &str, bugcrowd_approval_prompt_template: &str, session_logs_dir: &std::path::Path) -> anyhow::Result<(String, Vec<serde_json::Value>, Option<String>, Vec<serde_json::Value>)> {
+    use codex_core::protocol::EventMsg;
+    use crate::logged_command::LoggedCommand;
+    use crate::logged_command::format_exit_code;
     let mut assistant_content = String::new();
     let mut reasoning_content = String::new();
     let mut tool_calls = Vec::new();
     let mut tool_responses = Vec::new();
     let mut task_complete = false;
+    let mut commands_in_flight: std::collections::HashMap<String, (std::time::Instant, Vec<String>)> =
+        std::collections::HashMap::new();
     
     // Collect events until task is complete
     while !task_complete {
@@ -445,17 +816,19 @@ async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission
                         }
                         EventMsg::ExecCommandBegin(cmd) => {
                             println!("⚡ Executing: {:?}", cmd.command);
+                            commands_in_flight.insert(
+                                cmd.call_id.clone(),
+                                (std::time::Instant::now(), cmd.command.clone()),
+                            );
                             // Add bash command as a tool call
-                            tool_calls.push(serde_json::json!({
-                                "id": format!("exec_{}", cmd.call_id),
-                                "type": "function",
-                                "function": {
-                                    "name": "bash",
-                                    "arguments": serde_json::to_string(&serde_json::json!({
-                                        "command": cmd.command
-                                    })).unwrap_or_default()
-                                }
-                            }));
+                            tool_calls.push(
+                                crate::conversation_log::LogEntry::tool_call(
+                                    format!("exec_{}", cmd.call_id),
+                                    "bash",
+                                    serde_json::json!({ "command": cmd.command }),
+                                )
+                                .into_json(),
+                            );
                         }
                         EventMsg::ExecCommandEnd(result) => {
                             let stdout_preview = if result.stdout.len() > 200 {
@@ -464,16 +837,51 @@ async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission
                                 &result.stdout
                             };
                             println!("📊 Command result: {}", stdout_preview);
+
+                            let (duration, command) = match commands_in_flight.remove(&result.call_id) {
+                                Some((started, command)) => (started.elapsed(), format!("{command:?}")),
+                                None => (std::time::Duration::default(), "<unknown command>".to_string()),
+                            };
+                            let logged_command = LoggedCommand {
+                                command,
+                                combined_output: format!("{}{}", result.stdout, result.stderr),
+                                exit_code: result.exit_code,
+                                duration,
+                            };
+                            let log_path = logged_command.write(session_logs_dir, &result.call_id);
+
+                            let mut response_content = serde_json::json!({
+                                "exit_code": result.exit_code,
+                                "exit_status": format_exit_code(result.exit_code),
+                                "stdout": result.stdout,
+                                "stderr": result.stderr
+                            });
+                            match &log_path {
+                                Ok(path) => {
+                                    response_content["command_log"] = serde_json::json!(path.display().to_string());
+                                }
+                                Err(e) => {
+                                    println!("⚠️ Failed to write command log for {}: {}", result.call_id, e);
+                                }
+                            }
+                            if result.exit_code != 0 {
+                                if let Ok(path) = &log_path {
+                                    println!(
+                                        "❌ Command exited with {}, full output: {:?}",
+                                        format_exit_code(result.exit_code),
+                                        path
+                                    );
+                                }
+                            }
+
                             // Add bash command result as a tool response
-                            tool_responses.push(serde_json::json!({
-                                "role": "tool",
-                                "tool_call_id": format!("exec_{}", result.call_id),
-                                "content": serde_json::to_string(&serde_json::json!({
-                                    "exit_code": result.exit_code,
-                                    "stdout": result.stdout,
-                                    "stderr": result.stderr
-                                })).unwrap_or_default()
-                            }));
+                            tool_responses.push(
+                                crate::conversation_log::LogEntry::tool_response(
+                                    format!("exec_{}", result.call_id),
+                                    serde_json::to_string(&response_content).unwrap_or_default(),
+                                )
+                                .into_json(),
+                            );
                         }
                         EventMsg::McpToolCallBegin(tool) => {
                             println!("🔧 Calling tool: {}", tool.tool);
@@ -489,25 +897,51 @@ async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission
                                     &tool.arguments
                                 );
                                 
-                                match generate_user_prompt(&tool_approval_prompt, driver_model).await {
-                                    Ok(response) => {
-                                        println!("🤖 External LLM response: {}", response);
-                                        let (approved, reasoning) = parse_approval_response(&response);
-                                        
-                                        if approved {
-                                            println!("✅ Bugcrowd submission approved by external LLM: {}", reasoning);
+                                match review_loop::run_review_loop(
+                                    &tool_approval_prompt,
+                                    driver_model,
+                                    driver_provider,
+                                    driver_providers,
+                                    review_loop::DEFAULT_MAX_REVIEW_STEPS,
+                                    &mut tool_calls,
+                                    &mut tool_responses,
+                                )
+                                .await
+                                {
+                                    Ok(review) => {
+                                        println!(
+                                            "🤖 External LLM decision: {} (confidence: {:?}): {}",
+                                            if review.approved { "approve" } else { "deny" },
+                                            review.confidence,
+                                            review.reasoning
+                                        );
+
+                                        if review.approved {
+                                            println!("✅ Bugcrowd submission approved by external LLM: {}", review.reasoning);
                                             // Let the tool call proceed normally
                                         } else {
-                                            println!("❌ Bugcrowd submission denied by external LLM: {}", reasoning);
-                                            
+                                            println!("❌ Bugcrowd submission denied by external LLM: {}", review.reasoning);
+
                                             // Create a fake tool response with the denial reasoning
-                                            // This prevents the actual MCP tool from being called
-                                            tool_responses.push(serde_json::json!({
-                                                "role": "tool",
-                                                "tool_call_id": tool.call_id,
-                                                "content": format!("❌ Bugcrowd submission denied by security review: {}", reasoning)
-                                            }));
-                                            
+                                            // and confidence, so the gating decision is auditable
+                                            // from the conversation log alone. This prevents the
+                                            // actual MCP tool from being called.
+                                            tool_responses.push(
+                                                crate::conversation_log::LogEntry::approval_decision(
+                                                    tool.call_id.clone(),
+                                                    false,
+                                                    review.reasoning.clone(),
+                                                    review.confidence,
+                                                    serde_json::to_string(&serde_json::json!({
+                                                        "decision": "deny",
+                                                        "reasoning": review.reasoning,
+                                                        "confidence": review.confidence,
+                                                        "summary": format!("❌ Bugcrowd submission denied by security review: {}", review.reasoning),
+                                                    })).unwrap_or_default(),
+                                                )
+                                                .into_json(),
+                                            );
+
                                             // Skip to next event - don't let this tool call proceed
                                             continue;
                                         }
@@ -516,11 +950,13 @@ async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission
                                         println!("❌ Error getting approval from external LLM: {}", e);
                                         
                                         // Create a tool response with the error
-                                        tool_responses.push(serde_json::json!({
-                                            "role": "tool",
-                                            "tool_call_id": tool.call_id,
-                                            "content": format!("❌ Bugcrowd submission failed due to approval error: {}", e)
-                                        }));
+                                        tool_responses.push(
+                                            crate::conversation_log::LogEntry::tool_response(
+                                                tool.call_id.clone(),
+                                                format!("❌ Bugcrowd submission failed due to approval error: {}", e),
+                                            )
+                                            .into_json(),
+                                        );
                                         
                                         // Skip to next event - don't let this tool call proceed
                                         continue;
@@ -528,35 +964,57 @@ async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission
                                 }
                             }
                             
+                            // Negotiate a local-socket transport so an
+                            // interactive/full-screen tool can own stdio for
+                            // its own UI instead of sharing it with the codex
+                            // protocol stream. A tool that doesn't understand
+                            // `--local-socket` just ignores it and falls back
+                            // to stdio, same as any other unrecognized flag.
+                            let local_socket = local_socket::generate(&tool.tool);
+                            if let Err(e) = codex
+                                .submit(codex_core::protocol::Op::McpToolLocalSocket {
+                                    call_id: tool.call_id.clone(),
+                                    socket_name: local_socket.name.clone(),
+                                })
+                                .await
+                            {
+                                println!("⚠️ Failed to negotiate local-socket transport, falling back to stdio: {}", e);
+                            }
+
                             // Add tool call to OpenAI format
-                            tool_calls.push(serde_json::json!({
-                                "id": tool.call_id,
-                                "type": "function",
-                                "function": {
-                                    "name": tool.tool,
-                                    "arguments": serde_json::to_string(&tool.arguments).unwrap_or_default()
-                                }
-                            }));
+                            tool_calls.push(
+                                crate::conversation_log::LogEntry::tool_call_with_socket(
+                                    tool.call_id.clone(),
+                                    tool.tool.clone(),
+                                    tool.arguments.clone().unwrap_or(serde_json::Value::Null),
+                                    local_socket.name,
+                                )
+                                .into_json(),
+                            );
                         }
                         EventMsg::McpToolCallEnd(result) => {
                             match &result.result {
                                 Ok(success) => {
                                     println!("✅ Tool result: {:?}", success);
                                     // Add tool response to conversation log
-                                    tool_responses.push(serde_json::json!({
-                                        "role": "tool",
-                                        "tool_call_id": result.call_id,
-                                        "content": serde_json::to_string(success).unwrap_or_default()
-                                    }));
+                                    tool_responses.push(
+                                        crate::conversation_log::LogEntry::tool_response(
+                                            result.call_id.clone(),
+                                            serde_json::to_string(success).unwrap_or_default(),
+                                        )
+                                        .into_json(),
+                                    );
                                 }
                                 Err(err) => {
                                     println!("❌ Tool error: {}", err);
                                     // Add tool error to conversation log
-                                    tool_responses.push(serde_json::json!({
-                                        "role": "tool",
-                                        "tool_call_id": result.call_id,
-                                        "content": format!("Error: {}", err)
-                                    }));
+                                    tool_responses.push(
+                                        crate::conversation_log::LogEntry::tool_response(
+                                            result.call_id.clone(),
+                                            format!("Error: {}", err),
+                                        )
+                                        .into_json(),
+                                    );
                                 }
                             }
                         }
@@ -565,14 +1023,15 @@ async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission
                             
                             // Add approval request as a tool call
                             let approval_id = format!("approval_{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis());
-                            tool_calls.push(serde_json::json!({
-                                "id": approval_id.clone(),
-                                "type": "function",
-                                "function": {
-                                    "name": "request_approval",
-                                    "arguments": serde_json::to_string(&approval).unwrap_or_default()
+                            tool_calls.push(
+                                crate::conversation_log::LogEntry::ApprovalRequest {
+                                    id: approval_id.clone(),
+                                    command: approval.command.clone(),
+                                    cwd: approval.cwd.display().to_string(),
+                                    reason: approval.reason.clone(),
                                 }
-                            }));
+                                .into_json(),
+                            );
                             
                             // Check if it's a bugcrowd_submit call - always require external LLM approval
                             let is_bugcrowd_submit = approval.command.iter().any(|arg| 
@@ -595,36 +1054,64 @@ async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission
                             
                             println!("🤖 Requesting approval from external LLM{}...", context_info);
                             
-                            let decision = match generate_user_prompt(&approval_prompt, driver_model).await {
-                                Ok(response) => {
-                                    println!("🤖 External LLM response: {}", response);
-                                    if response.to_lowercase().contains("approve") {
-                                        println!("✅ Approved by external LLM");
-                                        codex_core::protocol::ReviewDecision::Approved
-                                    } else {
-                                        println!("❌ Denied by external LLM");
-                                        codex_core::protocol::ReviewDecision::Denied
-                                    }
+                            let review = match review_loop::run_review_loop(
+                                &approval_prompt,
+                                driver_model,
+                                driver_provider,
+                                driver_providers,
+                                review_loop::DEFAULT_MAX_REVIEW_STEPS,
+                                &mut tool_calls,
+                                &mut tool_responses,
+                            )
+                            .await
+                            {
+                                Ok(review) => {
+                                    println!(
+                                        "🤖 External LLM decision: {} (confidence: {:?}): {}",
+                                        if review.approved { "approve" } else { "deny" },
+                                        review.confidence,
+                                        review.reasoning
+                                    );
+                                    review
                                 }
                                 Err(e) => {
                                     println!("❌ Error getting approval from external LLM: {}", e);
-                                    codex_core::protocol::ReviewDecision::Denied
+                                    review_loop::ReviewResponse {
+                                        approved: false,
+                                        reasoning: format!("Approval request failed: {e}"),
+                                        confidence: None,
+                                    }
                                 }
                             };
-                            
-                            // Add approval decision as a tool response
-                            tool_responses.push(serde_json::json!({
-                                "role": "tool",
-                                "tool_call_id": approval_id,
-                                "content": serde_json::to_string(&serde_json::json!({
-                                    "decision": decision,
-                                    "llm_response": match &decision {
-                                        codex_core::protocol::ReviewDecision::Approved => "✅ Approved by external LLM",
-                                        codex_core::protocol::ReviewDecision::Denied => "❌ Denied by external LLM",
-                                        _ => "❓ Unknown decision"
-                                    }
-                                })).unwrap_or_default()
-                            }));
+                            let decision = if review.approved {
+                                codex_core::protocol::ReviewDecision::Approved
+                            } else {
+                                codex_core::protocol::ReviewDecision::Denied
+                            };
+
+                            // Add approval decision as a tool response, with
+                            // reasoning and confidence alongside it so the
+                            // gating decision is auditable from the
+                            // conversation log alone.
+                            tool_responses.push(
+                                crate::conversation_log::LogEntry::approval_decision(
+                                    approval_id.clone(),
+                                    review.approved,
+                                    review.reasoning.clone(),
+                                    review.confidence,
+                                    serde_json::to_string(&serde_json::json!({
+                                        "decision": decision,
+                                        "reasoning": review.reasoning,
+                                        "confidence": review.confidence,
+                                        "llm_response": if review.approved {
+                                            "✅ Approved by external LLM"
+                                        } else {
+                                            "❌ Denied by external LLM"
+                                        }
+                                    })).unwrap_or_default(),
+                                )
+                                .into_json(),
+                            );
                             
                             // Submit the approval decision back to codex
                             if let Err(e) = codex.submit(codex_core::protocol::Op::ExecApproval { 
@@ -639,59 +1126,61 @@ async fn collect_codex_response_with_tools(codex: &codex_core::Codex, submission
                         EventMsg::TaskStarted => {
                             println!("📝 Event: TaskStarted");
                             // Add as a system event
-                            tool_calls.push(serde_json::json!({
-                                "id": format!("event_taskstarted_{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis()),
-                                "type": "system",
-                                "function": {
-                                    "name": "task_started",
-                                    "arguments": "{}"
-                                }
-                            }));
+                            tool_calls.push(
+                                crate::conversation_log::LogEntry::system_event(
+                                    format!("event_taskstarted_{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis()),
+                                    "task_started",
+                                    serde_json::json!({}),
+                                )
+                                .into_json(),
+                            );
                         }
                         EventMsg::TokenCount(token_usage) => {
                             println!("📝 Event: TokenCount({:?})", token_usage);
                             // Add as a system event
-                            tool_calls.push(serde_json::json!({
-                                "id": format!("event_tokencount_{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis()),
-                                "type": "system",
-                                "function": {
-                                    "name": "token_count",
-                                    "arguments": serde_json::to_string(&token_usage).unwrap_or_default()
-                                }
-                            }));
+                            tool_calls.push(
+                                crate::conversation_log::LogEntry::system_event(
+                                    format!("event_tokencount_{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis()),
+                                    "token_count",
+                                    serde_json::to_value(&token_usage).unwrap_or_default(),
+                                )
+                                .into_json(),
+                            );
                         }
                         EventMsg::BackgroundEvent(bg_event) => {
                             println!("📝 Event: BackgroundEvent({})", bg_event.message);
                             // Add as a system event
-                            tool_calls.push(serde_json::json!({
-                                "id": format!("event_background_{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis()),
-                                "type": "system",
-                                "function": {
-                                    "name": "background_event",
-                                    "arguments": serde_json::to_string(&bg_event).unwrap_or_default()
-                                }
-                            }));
+                            tool_calls.push(
+                                crate::conversation_log::LogEntry::system_event(
+                                    format!("event_background_{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis()),
+                                    "background_event",
+                                    serde_json::to_value(&bg_event).unwrap_or_default(),
+                                )
+                                .into_json(),
+                            );
                         }
                         EventMsg::PatchApplyBegin(patch_event) => {
                             println!("🔧 Applying patch: {}", patch_event.call_id);
                             // Add as a tool call
-                            tool_calls.push(serde_json::json!({
-                                "id": format!("patch_{}", patch_event.call_id),
-                                "type": "function",
-                                "function": {
-                                    "name": "apply_patch",
-                                    "arguments": serde_json::to_string(&patch_event).unwrap_or_default()
+                            tool_calls.push(
+                                crate::conversation_log::LogEntry::PatchApplyBegin {
+                                    id: format!("patch_{}", patch_event.call_id),
+                                    detail: serde_json::to_value(&patch_event).unwrap_or_default(),
                                 }
-                            }));
+                                .into_json(),
+                            );
                         }
                         EventMsg::PatchApplyEnd(patch_result) => {
                             println!("✅ Patch applied: {}", patch_result.call_id);
                             // Add as a tool response
-                            tool_responses.push(serde_json::json!({
-                                "role": "tool",
-                                "tool_call_id": format!("patch_{}", patch_result.call_id),
-                                "content": serde_json::to_string(&patch_result).unwrap_or_default()
-                            }));
+                            tool_responses.push(
+                                crate::conversation_log::LogEntry::PatchApplyEnd {
+                                    role: "tool",
+                                    tool_call_id: format!("patch_{}", patch_result.call_id),
+                                    content: serde_json::to_string(&patch_result).unwrap_or_default(),
+                                }
+                                .into_json(),
+                            );
                         }
                         EventMsg::TaskComplete(_) => {
                             println!("✅ Task completed");
@@ -764,133 +1253,6 @@ fn inject_bugcrowd_approval_variables(
         .replace("{arguments}", &arguments_str)
 }
 
-fn parse_approval_response(response: &str) -> (bool, String) {
-    let response = response.trim();
-    
-    // Check if the response starts with APPROVE or DENY
-    if response.to_lowercase().starts_with("approve") {
-        // Extract reasoning after "APPROVE" (usually after " - " or just after the word)
-        let reasoning = if let Some(pos) = response.find(" - ") {
-            response[pos + 3..].trim().to_string()
-        } else if let Some(pos) = response.find("APPROVE") {
-            response[pos + 7..].trim().to_string()
-        } else if let Some(pos) = response.find("approve") {
-            response[pos + 7..].trim().to_string()
-        } else {
-            "No reasoning provided".to_string()
-        };
-        
-        (true, reasoning)
-    } else if response.to_lowercase().starts_with("deny") {
-        // Extract reasoning after "DENY"
-        let reasoning = if let Some(pos) = response.find(" - ") {
-            response[pos + 3..].trim().to_string()
-        } else if let Some(pos) = response.find("DENY") {
-            response[pos + 4..].trim().to_string()
-        } else if let Some(pos) = response.find("deny") {
-            response[pos + 4..].trim().to_string()
-        } else {
-            "No reasoning provided".to_string()
-        };
-        
-        (false, reasoning)
-    } else {
-        // If the response doesn't clearly start with APPROVE or DENY, auto-deny for safety
-        (false, format!("Unclear response format - auto-denied for safety: {}", response))
-    }
-}
-
-async fn generate_user_prompt(
-    driver_prompt: &str,
-    model: &str,
-) -> anyhow::Result<String> {
-    use codex_core::client::ModelClient;
-    use codex_core::model_provider_info::{ModelProviderInfo, WireApi};
-    use codex_core::config_types::{ReasoningEffort, ReasoningSummary};
-    use codex_core::client_common::Prompt;
-    use codex_core::models::{ResponseItem, ContentItem};
-    use futures::StreamExt;
-    
-    println!("🔄 Calling {} with driver prompt...", model);
-    
-    // Create model provider info
-    let provider = ModelProviderInfo {
-        name: "OpenAI".to_string(),
-        base_url: "https://api.openai.com/v1".to_string(),
-        env_key: Some("OPENAI_API_KEY".to_string()),
-        env_key_instructions: None,
-        wire_api: WireApi::Chat,
-        query_params: None,
-        env_http_headers: None,
-        http_headers: None,
-    };
-    
-    // Create model client
-    let client = ModelClient::new(
-        model,
-        provider,
-        ReasoningEffort::Medium,
-        ReasoningSummary::None,
-    );
-    
-    // Create prompt with driver prompt as user message
-    let user_message = ResponseItem::Message {
-        role: "user".to_string(),
-        content: vec![ContentItem::InputText {
-            text: driver_prompt.to_string(),
-        }],
-    };
-    
-    let prompt = Prompt {
-        input: vec![user_message],
-        prev_id: None,
-        user_instructions: None,
-        store: false,
-        extra_tools: std::collections::HashMap::new(),
-    };
-    
-    // Make the API call
-    let mut response_stream = client.stream(&prompt).await
-        .with_context(|| "Failed to create response stream")?;
-    
-    let mut response_text = String::new();
-    
-    // Collect the response
-    while let Some(event) = response_stream.next().await {
-        match event {
-            Ok(response_event) => {
-                match response_event {
-                    codex_core::client_common::ResponseEvent::OutputItemDone(item) => {
-                        if let ResponseItem::Message { content, .. } = item {
-                            for content_item in content {
-                                if let ContentItem::OutputText { text } = content_item {
-                                    response_text.push_str(&text);
-                                }
-                            }
-                        }
-                    }
-                    codex_core::client_common::ResponseEvent::Completed { .. } => {
-                        break;
-                    }
-                    _ => {
-                        // Ignore other events like Created
-                    }
-                }
-            }
-            Err(e) => {
-                return Err(anyhow::anyhow!("Error in response stream: {}", e));
-            }
-        }
-    }
-    
-    if response_text.is_empty() {
-        return Err(anyhow::anyhow!("No response received from external LLM"));
-    }
-    
-    Ok(response_text.trim().to_string())
-}
-
-
 /// Prepend root-level overrides so they have lower precedence than
 /// CLI-specific ones specified after the subcommand (if any).
 fn prepend_config_flags(
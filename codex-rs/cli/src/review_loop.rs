@@ -0,0 +1,433 @@
+//! Multi-step function-calling loop for approval/review decisions.
+//!
+//! A single-shot review call only sees whatever context got baked into the
+//! prompt up front (the command, its cwd, the bugcrowd payload, ...). For a
+//! risky `ExecApprovalRequest` -- especially a bugcrowd submission -- the
+//! reviewer often needs to look at the repo or re-read a diff before it can
+//! decide. This loop offers the reviewer a small set of read-only tools
+//! (`read_file`, `list_dir`, `grep`) alongside `submit_review_decision` and
+//! keeps calling the model, feeding tool results back in, until it submits a
+//! decision or `max_steps` is exhausted -- which is treated as an auto-deny,
+//! same as a reviewer that never answers.
+//!
+//! Every intermediate tool call and result is appended to the caller's
+//! `tool_calls`/`tool_responses` logs (the same logs `collect_codex_response_with_tools`
+//! already writes to) so the full deliberation, not just the final verdict,
+//! is captured in the session's conversation log.
+
+use std::fs;
+use std::io::Write;
+
+use futures::StreamExt;
+use tokio::time::Duration;
+use tokio::time::sleep;
+
+use rand::Rng;
+
+use crate::conversation_log::LogEntry;
+use crate::driver_backend;
+use crate::driver_backend::DriverError;
+use crate::driver_backend::DriverFunctionCall;
+use crate::driver_backend::DriverStreamEvent;
+
+/// How many read-only tool round-trips a reviewer gets before auto-deny.
+pub const DEFAULT_MAX_REVIEW_STEPS: u32 = 5;
+/// How many attempts (the first call plus its retries) a step gets while the
+/// backend keeps failing transiently (timeout, 429, 5xx, a dropped stream)
+/// before it gives up. A permanent failure (bad
+/// auth, an empty response) never consumes this budget -- it fails the step
+/// immediately.
+const MAX_RETRY_ATTEMPTS: u32 = 3;
+const BASE_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+fn backoff_with_jitter(attempt: u32) -> Duration {
+    let base = BASE_BACKOFF * 2u32.saturating_pow(attempt);
+    let capped = base.min(MAX_BACKOFF);
+    let jitter_ms = rand::thread_rng().gen_range(0..=capped.as_millis() as u64 / 2);
+    capped + Duration::from_millis(jitter_ms)
+}
+
+/// Calls `backend.generate_step_stream`, retrying transient failures with
+/// jittered exponential backoff and giving up immediately on a permanent
+/// one. A retry re-runs the whole stream from scratch -- there is no partial
+/// credit for text already printed on a failed attempt.
+async fn generate_step_with_retry(
+    backend: &dyn driver_backend::DriverBackend,
+    conversation: &[serde_json::Value],
+    functions: &serde_json::Value,
+) -> Result<(String, Vec<DriverFunctionCall>), DriverError> {
+    let mut attempt = 0;
+    loop {
+        match drain_step_stream(backend, conversation, functions).await {
+            Ok(result) => return Ok(result),
+            Err(e) if !e.is_transient() => return Err(e),
+            Err(e) if attempt + 1 >= MAX_RETRY_ATTEMPTS => return Err(e),
+            Err(e) => {
+                let backoff = backoff_with_jitter(attempt);
+                println!(
+                    "⚠️ Driver call attempt {} failed ({e}), retrying in {backoff:?}",
+                    attempt + 1
+                );
+                sleep(backoff).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Drains one `generate_step_stream` call, printing each `TextChunk` to the
+/// console as it arrives so the reviewer's reasoning shows up token-by-token
+/// instead of only appearing once the whole step has finished.
+async fn drain_step_stream(
+    backend: &dyn driver_backend::DriverBackend,
+    conversation: &[serde_json::Value],
+    functions: &serde_json::Value,
+) -> Result<(String, Vec<DriverFunctionCall>), DriverError> {
+    let mut stream = backend.generate_step_stream(conversation, functions).await;
+    while let Some(event) = stream.next().await {
+        match event? {
+            DriverStreamEvent::TextChunk(chunk) => {
+                print!("{chunk}");
+                let _ = std::io::stdout().flush();
+            }
+            DriverStreamEvent::Done { text, tool_calls } => {
+                println!();
+                return Ok((text, tool_calls));
+            }
+        }
+    }
+    Err(DriverError::Permanent("Stream ended without a Done event".to_string()))
+}
+
+/// Parsed `submit_review_decision` tool call.
+pub struct ReviewResponse {
+    pub approved: bool,
+    pub reasoning: String,
+    pub confidence: Option<f64>,
+}
+
+/// Functions offered to the reviewer: the read-only recon tools plus the
+/// `submit_review_decision` call that ends the loop. Forcing the final
+/// answer through a function (rather than scanning prose) means the
+/// decision, reasoning, and confidence all arrive as structured arguments.
+fn review_function_schemas() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "name": "read_file",
+            "description": "Read a UTF-8 text file relative to the current working directory.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" }
+                },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "list_dir",
+            "description": "List the entries of a directory relative to the current working directory.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" }
+                },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "grep",
+            "description": "Search the current working directory tree for lines matching a literal substring.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string" }
+                },
+                "required": ["pattern"]
+            }
+        },
+        {
+            "name": "submit_review_decision",
+            "description": "Submit the approve/deny decision for the command or tool call under review.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "decision": { "type": "string", "enum": ["approve", "deny"] },
+                    "reasoning": { "type": "string" },
+                    "confidence": {
+                        "type": "number",
+                        "description": "Confidence in this decision, from 0.0 to 1.0."
+                    }
+                },
+                "required": ["decision", "reasoning"]
+            }
+        }
+    ])
+}
+
+/// Caps how much of a file/grep result gets handed back to the model, so one
+/// large file can't blow the conversation's context budget.
+const TOOL_RESULT_MAX_CHARS: usize = 8000;
+/// Caps how many files `grep` walks, so a reviewer can't be made to scan an
+/// enormous tree one call at a time.
+const GREP_MAX_FILES: usize = 2000;
+/// Caps how many matching lines `grep` returns.
+const GREP_MAX_MATCHES: usize = 200;
+
+fn truncate(s: String) -> String {
+    if s.len() > TOOL_RESULT_MAX_CHARS {
+        // `s[..TOOL_RESULT_MAX_CHARS]` would panic if that byte offset lands
+        // mid-codepoint (e.g. a file full of multi-byte UTF-8); walk back to
+        // the nearest char boundary instead.
+        let mut end = TOOL_RESULT_MAX_CHARS;
+        while end > 0 && !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...[truncated]", &s[..end])
+    } else {
+        s
+    }
+}
+
+/// Rejects any path that escapes the current working directory, so a
+/// reviewer can't be tricked into reading `/etc/passwd` or similar via `..`.
+fn resolve_within_cwd(path: &str) -> Result<std::path::PathBuf, String> {
+    let cwd = std::env::current_dir().map_err(|e| format!("Failed to resolve cwd: {e}"))?;
+    let requested = cwd.join(path);
+    let canonical = requested
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {path}: {e}"))?;
+    if !canonical.starts_with(&cwd) {
+        return Err(format!("{path} is outside the working directory"));
+    }
+    Ok(canonical)
+}
+
+fn execute_review_tool(call: &DriverFunctionCall) -> String {
+    match call.name.as_str() {
+        "read_file" => {
+            let Some(path) = call.arguments.get("path").and_then(|v| v.as_str()) else {
+                return "Missing `path` argument".to_string();
+            };
+            match resolve_within_cwd(path).and_then(|p| fs::read_to_string(&p).map_err(|e| format!("Failed to read {path}: {e}"))) {
+                Ok(contents) => truncate(contents),
+                Err(e) => e,
+            }
+        }
+        "list_dir" => {
+            let path = call.arguments.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+            match resolve_within_cwd(path).and_then(|p| fs::read_dir(&p).map_err(|e| format!("Failed to list {path}: {e}"))) {
+                Ok(entries) => {
+                    let mut names: Vec<String> = entries
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.file_name().to_string_lossy().into_owned())
+                        .collect();
+                    names.sort();
+                    truncate(names.join("\n"))
+                }
+                Err(e) => e,
+            }
+        }
+        "grep" => {
+            let Some(pattern) = call.arguments.get("pattern").and_then(|v| v.as_str()) else {
+                return "Missing `pattern` argument".to_string();
+            };
+            grep_cwd(pattern)
+        }
+        other => format!("Unknown review function: {other}"),
+    }
+}
+
+/// Walks the working directory tree looking for `pattern` as a literal
+/// substring, bounded by `GREP_MAX_FILES`/`GREP_MAX_MATCHES` rather than
+/// shelling out to `grep -r`, so the result stays predictable regardless of
+/// what's installed on the host.
+fn grep_cwd(pattern: &str) -> String {
+    let cwd = match std::env::current_dir() {
+        Ok(cwd) => cwd,
+        Err(e) => return format!("Failed to resolve cwd: {e}"),
+    };
+
+    let mut matches = Vec::new();
+    let mut files_scanned = 0usize;
+    let mut stack = vec![cwd.clone()];
+
+    while let Some(dir) = stack.pop() {
+        if files_scanned >= GREP_MAX_FILES || matches.len() >= GREP_MAX_MATCHES {
+            break;
+        }
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        for entry in entries.filter_map(|e| e.ok()) {
+            if files_scanned >= GREP_MAX_FILES || matches.len() >= GREP_MAX_MATCHES {
+                break;
+            }
+            let path = entry.path();
+            if path.file_name().and_then(|n| n.to_str()) == Some(".git") {
+                continue;
+            }
+            if path.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            files_scanned += 1;
+            let Ok(contents) = fs::read_to_string(&path) else { continue };
+            for (line_num, line) in contents.lines().enumerate() {
+                if line.contains(pattern) {
+                    let rel = path.strip_prefix(&cwd).unwrap_or(&path);
+                    matches.push(format!("{}:{}: {}", rel.display(), line_num + 1, line.trim()));
+                    if matches.len() >= GREP_MAX_MATCHES {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    if matches.is_empty() {
+        format!("No matches for {pattern:?}")
+    } else {
+        truncate(matches.join("\n"))
+    }
+}
+
+/// Runs the reviewer's function-calling loop to completion, appending every
+/// intermediate tool call/result to `tool_calls`/`tool_responses` as it goes.
+/// Returns an auto-deny `ReviewResponse` if the model never calls
+/// `submit_review_decision` within `max_steps`.
+pub async fn run_review_loop(
+    driver_prompt: &str,
+    model: &str,
+    driver_provider: Option<&str>,
+    driver_providers: &[driver_backend::DriverProviderConfig],
+    max_steps: u32,
+    tool_calls: &mut Vec<serde_json::Value>,
+    tool_responses: &mut Vec<serde_json::Value>,
+) -> anyhow::Result<ReviewResponse> {
+    let backend = driver_backend::backend_for(model, driver_provider, driver_providers);
+    let functions = review_function_schemas();
+    let mut conversation: Vec<serde_json::Value> = vec![serde_json::json!({
+        "role": "user",
+        "content": driver_prompt,
+    })];
+
+    for step in 0..max_steps {
+        let (_assistant_text, calls) = match generate_step_with_retry(backend.as_ref(), &conversation, &functions).await {
+            Ok(result) => result,
+            Err(e) => {
+                let kind = if e.is_transient() { "transient (retries exhausted)" } else { "permanent" };
+                let reasoning = format!("Driver call failed with a {kind} error on step {}: {}", step + 1, e.message());
+
+                tool_responses.push(
+                    LogEntry::tool_response(format!("review_error_{step}"), reasoning.clone()).into_json(),
+                );
+
+                return Ok(ReviewResponse { approved: false, reasoning, confidence: None });
+            }
+        };
+
+        if let Some(submit) = calls.iter().find(|c| c.name == "submit_review_decision") {
+            let decision = submit.arguments.get("decision").and_then(|v| v.as_str()).unwrap_or("deny");
+            let reasoning = submit
+                .arguments
+                .get("reasoning")
+                .and_then(|v| v.as_str())
+                .unwrap_or("No reasoning provided")
+                .to_string();
+            let confidence = submit.arguments.get("confidence").and_then(|v| v.as_f64());
+
+            tool_calls.push(LogEntry::tool_call(submit.id.clone(), submit.name.clone(), submit.arguments.clone()).into_json());
+
+            return Ok(ReviewResponse {
+                approved: decision.eq_ignore_ascii_case("approve"),
+                reasoning,
+                confidence,
+            });
+        }
+
+        if calls.is_empty() {
+            // No function call at all -- nothing to execute and nothing to
+            // loop on, so treat it the same as budget exhaustion below.
+            break;
+        }
+
+        conversation.push(serde_json::json!({
+            "role": "assistant",
+            "tool_calls": calls.iter().map(|c| serde_json::json!({
+                "id": c.id,
+                "type": "function",
+                "function": { "name": c.name, "arguments": c.arguments },
+            })).collect::<Vec<_>>(),
+        }));
+
+        for call in &calls {
+            let result = execute_review_tool(call);
+
+            tool_calls.push(LogEntry::tool_call(call.id.clone(), call.name.clone(), call.arguments.clone()).into_json());
+            tool_responses.push(LogEntry::tool_response(call.id.clone(), result.clone()).into_json());
+
+            conversation.push(serde_json::json!({
+                "role": "tool",
+                "tool_call_id": call.id,
+                "content": result,
+            }));
+        }
+    }
+
+    Ok(ReviewResponse {
+        approved: false,
+        reasoning: format!("Reviewer exhausted its {max_steps}-step budget without calling submit_review_decision; auto-denied for safety"),
+        confidence: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `resolve_within_cwd` reads `std::env::current_dir()`, so these tests
+    /// chdir into a scratch directory for their duration; run serially
+    /// (`cargo test -- --test-threads=1` for this module) to avoid racing
+    /// other tests that depend on the process-wide cwd.
+    fn with_scratch_cwd<T>(f: impl FnOnce(&std::path::Path) -> T) -> T {
+        let original = std::env::current_dir().unwrap();
+        let scratch = std::env::temp_dir().join(format!(
+            "review_loop_test_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(scratch.join("sub")).unwrap();
+        fs::write(scratch.join("inside.txt"), "inside").unwrap();
+        std::env::set_current_dir(&scratch).unwrap();
+
+        let result = f(&scratch);
+
+        std::env::set_current_dir(original).unwrap();
+        fs::remove_dir_all(&scratch).unwrap();
+        result
+    }
+
+    #[test]
+    fn resolve_within_cwd_accepts_paths_inside_the_tree() {
+        with_scratch_cwd(|_scratch| {
+            assert!(resolve_within_cwd("inside.txt").is_ok());
+            assert!(resolve_within_cwd("sub").is_ok());
+            assert!(resolve_within_cwd(".").is_ok());
+        });
+    }
+
+    #[test]
+    fn resolve_within_cwd_rejects_escaping_paths() {
+        with_scratch_cwd(|_scratch| {
+            // Escapes via `..` into the scratch dir's parent, which exists
+            // but is outside the allowed tree.
+            assert!(resolve_within_cwd("../").is_err());
+            // An absolute path replaces the cwd join entirely (per
+            // `Path::join`), so this is the classic "just ask for
+            // /etc/passwd" escape -- exists on Linux, still rejected.
+            assert!(resolve_within_cwd("/etc/passwd").is_err());
+        });
+    }
+}
@@ -0,0 +1,218 @@
+//! Background "service" mode for `artemis autonomous --service`.
+//!
+//! Autonomous runs default to 30 minutes and often run much longer, but
+//! today they only exist in the foreground, writing `println!` output to
+//! whatever terminal launched them. This hands the run off to the host's
+//! service manager instead -- a launchd agent on macOS, a systemd `--user`
+//! unit on Linux -- so it keeps going after the terminal closes, and gives
+//! `artemis autonomous log` something to attach to later.
+//!
+//! Tailing is done by polling the service's log file's size and emitting
+//! newly-appended bytes, the same technique `codex logs tail` uses for
+//! `realtime_context.txt`, rather than an inotify/kqueue watch: it works the
+//! same way on every platform and needs no extra dependency.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
+use tokio::time::sleep;
+
+/// Unit/plist label for a given session, derived from its timestamp so
+/// concurrent sessions don't collide.
+fn service_name(session_timestamp: u64) -> String {
+    format!("com.artemis.autonomous.{session_timestamp}")
+}
+
+/// Session timestamp embedded in `./logs/autonomous_session_{timestamp}`,
+/// recovered from the directory name so `artemis autonomous log` can derive
+/// the service name without the caller having to pass it separately.
+fn session_timestamp_from_log_dir(log_dir: &Path) -> Option<u64> {
+    log_dir
+        .file_name()?
+        .to_str()?
+        .strip_prefix("autonomous_session_")?
+        .parse()
+        .ok()
+}
+
+/// Installs and starts a service that runs `exe` with `args` (the same
+/// `artemis autonomous ...` invocation that requested `--service`, with
+/// `--service` itself stripped so the child just runs the loop in the
+/// foreground of its own service process), redirecting its output to
+/// `session_logs_dir/service.log`.
+///
+/// Known limitation: ad-hoc `-c key=value` config overrides on the
+/// requesting invocation are not forwarded to the service unit; pass a
+/// `--config-file` that already has what you need when using `--service`.
+pub fn install_and_start(
+    session_timestamp: u64,
+    session_logs_dir: &Path,
+    args: &[String],
+) -> anyhow::Result<PathBuf> {
+    let exe = std::env::current_exe().context("Failed to resolve current executable")?;
+    let log_path = session_logs_dir.join("service.log");
+    let name = service_name(session_timestamp);
+
+    if cfg!(target_os = "macos") {
+        install_launchd(&name, &exe, args, &log_path)?;
+    } else if cfg!(target_os = "linux") {
+        install_systemd(&name, &exe, args, &log_path)?;
+    } else {
+        anyhow::bail!("--service is only supported on macOS (launchd) and Linux (systemd)");
+    }
+
+    Ok(log_path)
+}
+
+fn install_launchd(name: &str, exe: &Path, args: &[String], log_path: &Path) -> anyhow::Result<()> {
+    let home = std::env::var("HOME").context("HOME is not set")?;
+    let agents_dir = PathBuf::from(home).join("Library/LaunchAgents");
+    std::fs::create_dir_all(&agents_dir)
+        .with_context(|| format!("Failed to create {agents_dir:?}"))?;
+    let plist_path = agents_dir.join(format!("{name}.plist"));
+
+    let program_arguments = std::iter::once(exe.display().to_string())
+        .chain(args.iter().cloned())
+        .map(|arg| format!("        <string>{}</string>", xml_escape(&arg)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{name}</string>
+    <key>ProgramArguments</key>
+    <array>
+{program_arguments}
+    </array>
+    <key>StandardOutPath</key>
+    <string>{log_path}</string>
+    <key>StandardErrorPath</key>
+    <string>{log_path}</string>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        log_path = log_path.display(),
+    );
+
+    std::fs::write(&plist_path, plist).with_context(|| format!("Failed to write {plist_path:?}"))?;
+
+    run_checked(
+        std::process::Command::new("launchctl").args(["load", "-w"]).arg(&plist_path),
+        "launchctl load",
+    )
+}
+
+fn install_systemd(name: &str, exe: &Path, args: &[String], log_path: &Path) -> anyhow::Result<()> {
+    let home = std::env::var("HOME").context("HOME is not set")?;
+    let units_dir = PathBuf::from(home).join(".config/systemd/user");
+    std::fs::create_dir_all(&units_dir).with_context(|| format!("Failed to create {units_dir:?}"))?;
+    let unit_path = units_dir.join(format!("{name}.service"));
+
+    let exec_start = std::iter::once(shell_quote(&exe.display().to_string()))
+        .chain(args.iter().map(|a| shell_quote(a)))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let unit = format!(
+        r#"[Unit]
+Description=Artemis autonomous session {name}
+
+[Service]
+Type=simple
+ExecStart={exec_start}
+StandardOutput=append:{log_path}
+StandardError=inherit
+Restart=no
+
+[Install]
+WantedBy=default.target
+"#,
+        log_path = log_path.display(),
+    );
+
+    std::fs::write(&unit_path, unit).with_context(|| format!("Failed to write {unit_path:?}"))?;
+
+    run_checked(std::process::Command::new("systemctl").args(["--user", "daemon-reload"]), "systemctl daemon-reload")?;
+    run_checked(
+        std::process::Command::new("systemctl").args(["--user", "enable", "--now", &format!("{name}.service")]),
+        "systemctl enable --now",
+    )
+}
+
+fn run_checked(command: &mut std::process::Command, what: &str) -> anyhow::Result<()> {
+    let status = command.status().with_context(|| format!("Failed to run {what}"))?;
+    if !status.success() {
+        anyhow::bail!("{what} exited with {status}");
+    }
+    Ok(())
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Streams a session's output: by default polls `log_dir/service.log` for
+/// newly-appended bytes, the same way `codex logs tail` polls
+/// `realtime_context.txt`; with `journal: true` on Linux, delegates to
+/// `journalctl --user -u <unit> -f` instead. The unit `install_systemd`
+/// writes sends the run's stdout and stderr to `service.log`, not to the
+/// journal, so the journal shows systemd's own records for the unit (start,
+/// stop, exit) rather than the run's output.
+pub async fn tail_log(log_dir: &Path, journal: bool) -> anyhow::Result<()> {
+    let session_timestamp = session_timestamp_from_log_dir(log_dir).ok_or_else(|| {
+        anyhow::anyhow!("{log_dir:?} doesn't look like a `./logs/autonomous_session_<timestamp>` directory")
+    })?;
+
+    if journal {
+        if !cfg!(target_os = "linux") {
+            anyhow::bail!("--journal delegates to systemd's journal, which is only available on Linux");
+        }
+        let name = service_name(session_timestamp);
+        let status = tokio::process::Command::new("journalctl")
+            .args(["--user", "-u", &format!("{name}.service"), "-f"])
+            .status()
+            .await
+            .context("Failed to run journalctl")?;
+        if !status.success() {
+            anyhow::bail!("journalctl exited with {status}");
+        }
+        return Ok(());
+    }
+
+    let path = log_dir.join("service.log");
+    while tokio::fs::metadata(&path).await.is_err() {
+        println!("⏳ Waiting for {path:?} to be created by the service...");
+        sleep(Duration::from_secs(1)).await;
+    }
+
+    let mut file = tokio::fs::File::open(&path)
+        .await
+        .with_context(|| format!("Failed to open {path:?}"))?;
+    let mut position = 0u64;
+
+    loop {
+        let metadata = file.metadata().await?;
+        if metadata.len() > position {
+            file.seek(std::io::SeekFrom::Start(position)).await?;
+            let mut buf = String::new();
+            file.read_to_string(&mut buf).await?;
+            print!("{buf}");
+            position = metadata.len();
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+}
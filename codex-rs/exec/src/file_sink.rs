@@ -0,0 +1,189 @@
+//! The original `RealtimeLogger` behavior — `realtime_context.txt` and
+//! `realtime_conversation.json` on disk — reimplemented as a [`LogSink`].
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tokio::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+use crate::log_sink::FinalResult;
+use crate::log_sink::LogEntry;
+use crate::log_sink::LogSink;
+
+/// The writer task flushes whichever comes first: this many milliseconds
+/// since the last flush, or this many queued records.
+const WRITER_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+const WRITER_FLUSH_BATCH_SIZE: usize = 32;
+
+struct WriteRecord {
+    context_line: String,
+    conversation_entry: Option<serde_json::Value>,
+}
+
+enum WriterCommand {
+    Record(WriteRecord),
+    Flush(oneshot::Sender<()>),
+    Shutdown(oneshot::Sender<()>),
+}
+
+/// Writes `realtime_context.txt` and `realtime_conversation.json` under
+/// `log_dir`, with disk I/O owned by a background task so `record()` is a
+/// cheap non-blocking `send`.
+pub struct FileSink {
+    log_dir: PathBuf,
+    writer_tx: mpsc::UnboundedSender<WriterCommand>,
+}
+
+impl FileSink {
+    pub fn new(log_dir: &Path, instance_id: &str, initial_prompt: &str) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(log_dir)?;
+
+        let context_path = log_dir.join("realtime_context.txt");
+        let json_path = log_dir.join("realtime_conversation.json");
+
+        let mut context_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&context_path)?;
+        let json_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&json_path)?;
+
+        let start_time = chrono::Utc::now();
+        context_file.write_all(
+            format!(
+                "=== CODEX INSTANCE: {} ===\nStarted: {}\nTask: {}\n\n",
+                instance_id,
+                start_time.format("%Y-%m-%d %H:%M:%S UTC"),
+                initial_prompt
+            )
+            .as_bytes(),
+        )?;
+        context_file.flush()?;
+
+        let (writer_tx, writer_rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_writer(context_file, json_file, writer_rx));
+
+        Ok(Self {
+            log_dir: log_dir.to_path_buf(),
+            writer_tx,
+        })
+    }
+}
+
+#[async_trait]
+impl LogSink for FileSink {
+    async fn record(&self, entry: &LogEntry) -> anyhow::Result<()> {
+        self.writer_tx
+            .send(WriterCommand::Record(WriteRecord {
+                context_line: entry.context_line.clone(),
+                conversation_entry: entry.value.clone(),
+            }))
+            .map_err(|_| anyhow::anyhow!("file sink writer task is gone"))
+    }
+
+    async fn finalize(&self, result: &FinalResult) -> anyhow::Result<()> {
+        self.flush().await?;
+        let final_result = serde_json::json!({
+            "instance_id": result.instance_id,
+            "status": result.status,
+            "started_at": result.started_at.to_rfc3339(),
+            "completed_at": result.completed_at.to_rfc3339(),
+            "conversation": result.conversation,
+            "usage": result.usage,
+        });
+        let result_path = self.log_dir.join("final_result.json");
+        tokio::fs::write(&result_path, serde_json::to_string_pretty(&final_result)?).await?;
+        Ok(())
+    }
+
+    async fn flush(&self) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.writer_tx
+            .send(WriterCommand::Flush(tx))
+            .map_err(|_| anyhow::anyhow!("file sink writer task is gone"))?;
+        rx.await
+            .map_err(|_| anyhow::anyhow!("file sink writer task dropped flush ack"))
+    }
+
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.writer_tx
+            .send(WriterCommand::Shutdown(tx))
+            .map_err(|_| anyhow::anyhow!("file sink writer task is gone"))?;
+        rx.await
+            .map_err(|_| anyhow::anyhow!("file sink writer task dropped shutdown ack"))
+    }
+}
+
+async fn run_writer(
+    mut context_file: std::fs::File,
+    mut json_file: std::fs::File,
+    mut rx: mpsc::UnboundedReceiver<WriterCommand>,
+) {
+    let mut pending_context = String::new();
+    let mut pending_entries: Vec<serde_json::Value> = Vec::new();
+    let mut ticker = tokio::time::interval(WRITER_FLUSH_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let flush_now = |context_file: &mut std::fs::File,
+                      json_file: &mut std::fs::File,
+                      pending_context: &mut String,
+                      pending_entries: &mut Vec<serde_json::Value>| {
+        if !pending_context.is_empty() {
+            let _ = context_file.write_all(pending_context.as_bytes());
+            let _ = context_file.flush();
+            pending_context.clear();
+        }
+        for entry in pending_entries.drain(..) {
+            if let Ok(mut line) = serde_json::to_string(&entry) {
+                line.push('\n');
+                let _ = json_file.write_all(line.as_bytes());
+            }
+        }
+        let _ = json_file.flush();
+    };
+
+    loop {
+        tokio::select! {
+            biased;
+
+            cmd = rx.recv() => {
+                let Some(cmd) = cmd else { break };
+                match cmd {
+                    WriterCommand::Record(record) => {
+                        pending_context.push_str(&record.context_line);
+                        if let Some(entry) = record.conversation_entry {
+                            pending_entries.push(entry);
+                        }
+                        if pending_entries.len() >= WRITER_FLUSH_BATCH_SIZE {
+                            flush_now(&mut context_file, &mut json_file, &mut pending_context, &mut pending_entries);
+                        }
+                    }
+                    WriterCommand::Flush(ack) => {
+                        flush_now(&mut context_file, &mut json_file, &mut pending_context, &mut pending_entries);
+                        let _ = ack.send(());
+                    }
+                    WriterCommand::Shutdown(ack) => {
+                        flush_now(&mut context_file, &mut json_file, &mut pending_context, &mut pending_entries);
+                        let _ = ack.send(());
+                        break;
+                    }
+                }
+            }
+
+            _ = ticker.tick() => {
+                flush_now(&mut context_file, &mut json_file, &mut pending_context, &mut pending_entries);
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Original supervisor handshake: `status.json`/`followup_input.json` on a
+//! shared filesystem, woken by the `notify` watcher added for
+//! `wait_for_supervisor_followup`. Kept as the default [`SupervisorTransport`]
+//! since most deployments still run one instance per host directory.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use notify::Event as NotifyEvent;
+use notify::EventKind;
+use notify::RecursiveMode;
+use notify::Watcher;
+use tokio::time::Duration;
+use tokio::time::Instant;
+use tracing::debug;
+use tracing::error;
+use tracing::info;
+
+use crate::supervisor_transport::StatusUpdate;
+use crate::supervisor_transport::SupervisorTransport;
+
+pub struct FileTransport {
+    log_dir: PathBuf,
+    /// Capacity of the channel between the watcher's callback and
+    /// `await_followup`; see `--watcher-backlog`.
+    watcher_backlog: usize,
+}
+
+impl FileTransport {
+    pub fn new(log_dir: PathBuf, watcher_backlog: usize) -> Self {
+        Self {
+            log_dir,
+            watcher_backlog,
+        }
+    }
+
+    fn status_file(&self) -> PathBuf {
+        self.log_dir.join("status.json")
+    }
+
+    fn followup_file(&self) -> PathBuf {
+        self.log_dir.join("followup_input.json")
+    }
+}
+
+#[async_trait]
+impl SupervisorTransport for FileTransport {
+    async fn publish_status(&self, status: &StatusUpdate) -> anyhow::Result<()> {
+        std::fs::write(self.status_file(), serde_json::to_string_pretty(status)?)?;
+        Ok(())
+    }
+
+    async fn await_followup(
+        &self,
+        _message_index: usize,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<String>> {
+        let followup_file = self.followup_file();
+        info!("Waiting for supervisor followup...");
+
+        let (wake_tx, mut wake_rx) = tokio::sync::mpsc::channel::<()>(self.watcher_backlog.max(1));
+        let watched_path = followup_file.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<NotifyEvent>| {
+            let event = match res {
+                Ok(event) => event,
+                Err(e) => {
+                    error!("Filesystem watch error: {e:?}");
+                    return;
+                }
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                return;
+            }
+            if !event.paths.iter().any(|p| p == &watched_path) {
+                return;
+            }
+            // A full channel still leaves an unread wakeup queued, so the
+            // loop below re-checks `followup_file` on its next iteration;
+            // nothing is dropped, just coalesced.
+            if wake_tx.try_send(()).is_err() {
+                debug!("Watcher backlog full for {watched_path:?}, coalescing wakeup");
+            }
+        })?;
+        watcher.watch(&self.log_dir, RecursiveMode::NonRecursive)?;
+
+        let deadline = Instant::now() + timeout;
+
+        // The file may already exist from a write that landed before the
+        // watcher was armed, so check once before waiting on the channel.
+        loop {
+            if followup_file.exists() {
+                match std::fs::read_to_string(&followup_file) {
+                    Ok(content) => {
+                        if let Some(outcome) = parse_followup_payload(&content) {
+                            let _ = std::fs::remove_file(&followup_file);
+                            return Ok(outcome);
+                        }
+                    }
+                    Err(e) => error!("Failed to read followup file: {e:?}"),
+                }
+            }
+
+            tokio::select! {
+                _ = wake_rx.recv() => {}
+                _ = tokio::time::sleep_until(deadline) => {
+                    info!("Timeout waiting for supervisor followup, terminating");
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+/// Parses one `followup_input.json` payload: `Some(None)` for an explicit
+/// terminate (empty message or `"terminate": true`), `Some(Some(message))`
+/// for a followup prompt, `None` if the payload didn't parse and the caller
+/// should keep waiting.
+fn parse_followup_payload(content: &str) -> Option<Option<String>> {
+    let followup_json = match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(v) => v,
+        Err(e) => {
+            error!("Failed to parse followup JSON: {e:?}");
+            return None;
+        }
+    };
+
+    if let Some(message) = followup_json.get("message").and_then(|m| m.as_str()) {
+        if message.trim().is_empty() {
+            Some(None)
+        } else {
+            Some(Some(message.to_string()))
+        }
+    } else if followup_json
+        .get("terminate")
+        .and_then(|t| t.as_bool())
+        .unwrap_or(false)
+    {
+        Some(None)
+    } else {
+        None
+    }
+}
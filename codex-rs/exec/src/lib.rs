@@ -1,6 +1,15 @@
 mod cli;
 mod event_processor;
+mod file_sink;
+mod file_transport;
+mod log_sink;
+mod notifier;
+mod postgres_sink;
 mod realtime_logger;
+mod redis_transport;
+mod sse;
+mod supervisor_transport;
+mod token_accumulator;
 
 use std::io::IsTerminal;
 use std::io::Read;
@@ -21,7 +30,18 @@ use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::util::is_inside_git_repo;
 use event_processor::EventProcessor;
+use file_sink::FileSink;
+use file_transport::FileTransport;
+use log_sink::LogSink;
+use notifier::Notifier;
+use notifier::WebhookNotifier;
+use postgres_sink::PostgresSink;
 use realtime_logger::RealtimeLogger;
+use redis_transport::RedisTransport;
+use token_accumulator::Budget;
+use token_accumulator::price_for_model;
+use supervisor_transport::StatusUpdate;
+use supervisor_transport::SupervisorTransport;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
@@ -40,7 +60,17 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         last_message_file,
         log_session_dir,
         instance_id,
+        postgres_log_url,
+        webhook_urls,
+        budget_max_cost_usd,
+        budget_max_tokens,
         wait_for_followup,
+        watcher_backlog,
+        supervisor_transport,
+        output_format,
+        on_followup,
+        stop_timeout,
+        watch_config,
         mode,
         prompt,
         config_overrides,
@@ -120,7 +150,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     };
 
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides)?;
+    // Cloned rather than moved so `--watch-config` can re-run
+    // `load_with_cli_overrides` with the same CLI-level overrides later.
+    let mut config = Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides.clone())?;
     let mut event_processor =
         EventProcessor::create_with_ansi(stdout_with_ansi, !config.hide_agent_reasoning);
     // Print the effective configuration and prompt so users can see what Codex
@@ -150,21 +182,208 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     let codex = Arc::new(codex_wrapper);
     info!("Codex initialized with event: {event:?}");
 
+    let instance_id_str = instance_id
+        .clone()
+        .unwrap_or_else(|| format!("codex_{}", std::process::id()));
+
     // Initialize real-time logger if requested
     let realtime_logger = if let Some(ref log_dir) = log_session_dir {
-        let instance_id_str = instance_id
-            .clone()
-            .unwrap_or_else(|| format!("codex_{}", std::process::id()));
-        Some(Arc::new(RealtimeLogger::new(
+        let mut sinks: Vec<Box<dyn LogSink>> =
+            vec![Box::new(FileSink::new(log_dir, &instance_id_str, &prompt)?)];
+        if let Some(ref postgres_url) = postgres_log_url {
+            sinks.push(Box::new(PostgresSink::connect(postgres_url).await?));
+        }
+
+        let notifiers: Vec<Box<dyn Notifier>> = if webhook_urls.is_empty() {
+            Vec::new()
+        } else {
+            vec![Box::new(WebhookNotifier::new(webhook_urls.clone()))]
+        };
+
+        let price = price_for_model(model.as_deref().unwrap_or_default());
+        let budget = Budget {
+            max_cost_usd: budget_max_cost_usd,
+            max_total_tokens: budget_max_tokens,
+        };
+
+        Some(Arc::new(RealtimeLogger::with_all(
             log_dir.clone(),
-            instance_id_str,
+            instance_id_str.clone(),
             &prompt,
-            model.clone(),
-            config.specialist.clone(),
+            sinks,
+            notifiers,
+            price,
+            budget,
         )?))
     } else {
         None
     };
+    let mut budget_exceeded_rx = realtime_logger
+        .as_ref()
+        .map(|logger| logger.subscribe_budget_exceeded());
+
+    // Build the supervisor transport up front so the followup loop below
+    // doesn't care whether it's talking to a shared log directory or Redis.
+    // `Arc` (rather than `Box`) so the mid-turn watcher task spawned below
+    // can hold its own handle alongside the one used after each turn.
+    let supervisor_transport: Option<Arc<dyn SupervisorTransport>> = if wait_for_followup {
+        if let Some(redis_addr) = supervisor_transport.strip_prefix("redis://") {
+            Some(Arc::new(
+                RedisTransport::connect(&format!("redis://{redis_addr}"), &instance_id_str).await?,
+            ))
+        } else if supervisor_transport == "file" {
+            let log_dir = log_session_dir.clone().ok_or_else(|| {
+                anyhow::anyhow!("--supervisor-transport=file requires --log-session-dir")
+            })?;
+            Some(Arc::new(FileTransport::new(log_dir, watcher_backlog)))
+        } else {
+            anyhow::bail!("Unknown --supervisor-transport {supervisor_transport:?}");
+        }
+    } else {
+        None
+    };
+
+    // In `restart`/`ignore` mode a followup needs to be noticed while a turn
+    // is still running, not just after it completes, so a background task
+    // watches the transport continuously and forwards each one here. In
+    // `queue` mode (the original behavior) no such watcher runs; a followup
+    // is only consulted via `await_followup` after `TaskComplete`.
+    //
+    // The watcher and the post-turn `await_followup` call below both read
+    // from the same `transport`, so the watcher only polls it while
+    // `turn_active` is true; otherwise it parks, leaving the transport free
+    // for the post-turn wait instead of racing it for the same message
+    // (the race let the watcher swallow a followup meant for that wait).
+    let turn_active_tx = Arc::new(tokio::sync::watch::channel(false).0);
+    let mut mid_turn_followup_rx = if let (Some(transport), false) =
+        (&supervisor_transport, matches!(on_followup, cli::OnFollowup::Queue))
+    {
+        let (mid_tx, mid_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let transport = transport.clone();
+        let mut turn_active_rx = turn_active_tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                while !*turn_active_rx.borrow() {
+                    if turn_active_rx.changed().await.is_err() {
+                        return;
+                    }
+                }
+
+                tokio::select! {
+                    // The active turn ended while we were about to poll (or
+                    // mid-poll); loop back to the top and park there instead
+                    // of racing the post-turn `await_followup`.
+                    _ = turn_active_rx.changed() => continue,
+                    result = transport.await_followup(0, tokio::time::Duration::from_secs(300)) => {
+                        match result {
+                            Ok(Some(message)) => {
+                                if mid_tx.send(message).is_err() {
+                                    break;
+                                }
+                            }
+                            // No followup within this window; the watcher just
+                            // keeps going rather than treating a timeout as a
+                            // terminate.
+                            Ok(None) => continue,
+                            Err(e) => {
+                                error!("Mid-turn followup watcher error: {e:?}");
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        Some(mid_rx)
+    } else {
+        None
+    };
+
+    // Supervisors can watch this instance's events live over SSE instead of
+    // polling the log files, if `ARTEMIS_SSE_ADDR` is set (e.g.
+    // `127.0.0.1:8765`). The registry holds just this one instance, but the
+    // same address can be shared across multiple `codex exec` processes
+    // behind a reverse proxy.
+    if let (Some(ref logger), Ok(addr)) = (&realtime_logger, std::env::var("ARTEMIS_SSE_ADDR")) {
+        let registry = sse::LoggerRegistry::new();
+        registry.register(logger.clone()).await;
+        match addr.parse::<std::net::SocketAddr>() {
+            Ok(addr) => {
+                let router = sse::router(registry);
+                tokio::spawn(async move {
+                    match tokio::net::TcpListener::bind(addr).await {
+                        Ok(listener) => {
+                            if let Err(e) = axum::serve(listener, router).await {
+                                error!("SSE server error: {e:?}");
+                            }
+                        }
+                        Err(e) => error!("Failed to bind SSE server on {addr}: {e:?}"),
+                    }
+                });
+                info!("Serving realtime events over SSE at http://{addr}");
+            }
+            Err(e) => error!("Invalid ARTEMIS_SSE_ADDR {addr:?}: {e:?}"),
+        }
+    }
+
+    // Fired once the current turn actually winds down (`TaskComplete`/
+    // `Error`), so the SIGTERM/SIGHUP handler below can wait for a real
+    // drain instead of always sleeping out the full `--stop-timeout`.
+    let turn_complete_notify = Arc::new(tokio::sync::Notify::new());
+
+    // SIGTERM/SIGHUP get the same "interrupt, then give the turn a chance to
+    // drain" treatment Ctrl-C already gets from `ctrl_c`, plus a final
+    // `status.json` and logger flush so a supervisor never sees a stale
+    // `processing` status or a half-written log after the process exits.
+    #[cfg(unix)]
+    {
+        let codex = codex.clone();
+        let realtime_logger = realtime_logger.clone();
+        let log_session_dir = log_session_dir.clone();
+        let turn_complete_notify = turn_complete_notify.clone();
+        tokio::spawn(async move {
+            use tokio::signal::unix::SignalKind;
+            use tokio::signal::unix::signal;
+
+            let (mut sigterm, mut sighup) =
+                match (signal(SignalKind::terminate()), signal(SignalKind::hangup())) {
+                    (Ok(term), Ok(hup)) => (term, hup),
+                    (Err(e), _) | (_, Err(e)) => {
+                        error!("Failed to install SIGTERM/SIGHUP handlers: {e:?}");
+                        return;
+                    }
+                };
+
+            tokio::select! {
+                _ = sigterm.recv() => info!("Received SIGTERM, shutting down gracefully"),
+                _ = sighup.recv() => info!("Received SIGHUP, shutting down gracefully"),
+            }
+
+            let _ = codex.submit(Op::Interrupt).await;
+
+            if let Some(ref log_dir) = log_session_dir {
+                let status = serde_json::json!({"status": "terminated"});
+                let _ = std::fs::write(
+                    log_dir.join("status.json"),
+                    serde_json::to_string_pretty(&status).unwrap_or_default(),
+                );
+            }
+
+            tokio::select! {
+                _ = turn_complete_notify.notified() => {}
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(stop_timeout)) => {
+                    info!("--stop-timeout elapsed before the turn drained; exiting anyway");
+                }
+            }
+
+            if let Some(ref logger) = realtime_logger {
+                let _ = logger.flush().await;
+                let _ = logger.shutdown().await;
+            }
+
+            std::process::exit(0);
+        });
+    }
 
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Event>();
     {
@@ -225,20 +444,181 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
 
+    // In `--wait-for-followup` mode an instance can live across many turns,
+    // so `--watch-config` re-reads the resolved config file between turns
+    // instead of freezing `Config` for the whole process lifetime. The
+    // watcher fires on any write to the file's parent directory (editors
+    // commonly write-then-rename rather than writing in place), and the
+    // watcher callback filters for the exact path.
+    let mut config_changed_rx = None;
+    let mut _config_watcher = None;
+    if watch_config {
+        let config_path = codex_core::config::find_codex_home()?.join("config.toml");
+        let (config_tx, config_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+        let watched_path = config_path.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            match res {
+                Ok(event) => {
+                    if matches!(
+                        event.kind,
+                        notify::EventKind::Modify(_) | notify::EventKind::Create(_)
+                    ) && event.paths.iter().any(|p| p == &watched_path)
+                    {
+                        let _ = config_tx.send(());
+                    }
+                }
+                Err(e) => error!("Config watch error: {e:?}"),
+            }
+        })?;
+        if let Some(parent) = config_path.parent() {
+            watcher.watch(parent, notify::RecursiveMode::NonRecursive)?;
+        }
+        config_changed_rx = Some(config_rx);
+        // Held for `run_main`'s lifetime; dropping it would stop the watch.
+        _config_watcher = Some(watcher);
+    }
+
     // Send the prompt.
     let mut current_prompt = prompt;
     let mut message_index = 0;
 
-    loop {
+    'turns: loop {
+        if let Some(ref mut rx) = config_changed_rx {
+            // Drain every pending notification so a burst of writes only
+            // triggers one reload, then apply it before the next turn.
+            let mut saw_change = false;
+            while rx.try_recv().is_ok() {
+                saw_change = true;
+            }
+            if saw_change {
+                match Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides.clone())
+                {
+                    Ok(new_config) => {
+                        let mut changed_keys = Vec::new();
+                        if new_config.model != config.model {
+                            changed_keys.push("model");
+                        }
+                        if new_config.specialist != config.specialist {
+                            changed_keys.push("specialist");
+                        }
+                        if new_config.hide_agent_reasoning != config.hide_agent_reasoning {
+                            changed_keys.push("hide_agent_reasoning");
+                        }
+
+                        if !changed_keys.is_empty() {
+                            info!("--watch-config: applying changed keys: {changed_keys:?}");
+                            let _ = codex
+                                .submit(Op::OverrideTurnContext {
+                                    model: new_config.model.clone(),
+                                    specialist: new_config.specialist.clone(),
+                                })
+                                .await;
+                            if new_config.hide_agent_reasoning != config.hide_agent_reasoning {
+                                event_processor = EventProcessor::create_with_ansi(
+                                    stdout_with_ansi,
+                                    !new_config.hide_agent_reasoning,
+                                );
+                            }
+                        }
+                        config = new_config;
+                    }
+                    Err(e) => error!("--watch-config: failed to reload config.toml: {e:?}"),
+                }
+            }
+        }
+
         let items: Vec<InputItem> = vec![InputItem::Text {
             text: current_prompt.clone(),
         }];
         let task_id = codex.submit(Op::UserInput { items }).await?;
         info!("Sent prompt with event ID: {task_id}");
+        let _ = turn_active_tx.send(true);
 
         // Run the loop until the task is complete.
         let mut assistant_responded = false;
-        while let Some(event) = rx.recv().await {
+        loop {
+            let event = tokio::select! {
+                biased;
+
+                // Only fires once a configured `Budget` is crossed; takes priority
+                // over everything else so a run stops spending the moment it
+                // crosses the threshold instead of finishing out the turn.
+                _ = async {
+                    match budget_exceeded_rx.as_mut() {
+                        Some(rx) => {
+                            while !*rx.borrow() {
+                                if rx.changed().await.is_err() {
+                                    std::future::pending::<()>().await;
+                                }
+                            }
+                        }
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    error!("Budget exceeded; aborting the run");
+                    let _ = codex.submit(Op::Interrupt).await;
+                    if let Some(ref logger) = realtime_logger {
+                        let _ = logger.flush().await;
+                        let _ = logger.shutdown().await;
+                    }
+                    std::process::exit(1);
+                }
+
+                // Only polled when `mid_turn_followup_rx` is `Some`, i.e. in
+                // `restart`/`ignore` mode; once it's `None` (disabled, or the
+                // watcher task ended) this branch just never fires again.
+                watcher_result = async {
+                    match mid_turn_followup_rx.as_mut() {
+                        Some(rx) => rx.recv().await,
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    let Some(followup) = watcher_result else {
+                        // The watcher task ended (transport error); stop
+                        // polling it instead of busy-looping on a closed
+                        // channel.
+                        mid_turn_followup_rx = None;
+                        continue;
+                    };
+                    match on_followup {
+                        cli::OnFollowup::Restart => {
+                            info!("Mid-turn followup received; interrupting the current turn to restart with it");
+                            let _ = codex.submit(Op::Interrupt).await;
+                            // Drain until the turn actually winds down (the
+                            // interrupt surfaces as an `Error` event) before
+                            // starting the new one with the followup prompt.
+                            while let Some(event) = rx.recv().await {
+                                let is_turn_end = matches!(
+                                    &event.msg,
+                                    EventMsg::Error(_) | EventMsg::TaskComplete(_)
+                                );
+                                if let Some(ref logger) = realtime_logger {
+                                    if let Err(e) = logger.log_event(&event).await {
+                                        error!("Failed to log event to realtime logger: {e:?}");
+                                    }
+                                }
+                                if is_turn_end {
+                                    turn_complete_notify.notify_one();
+                                    break;
+                                }
+                            }
+                            current_prompt = followup;
+                            continue 'turns;
+                        }
+                        cli::OnFollowup::Ignore => {
+                            debug!("Discarding mid-turn followup (--on-followup ignore)");
+                            continue;
+                        }
+                        cli::OnFollowup::Queue => unreachable!("watcher is not spawned in queue mode"),
+                    }
+                }
+
+                event = rx.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+            };
+
             let (is_last_event, last_assistant_message) = match &event.msg {
                 EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
                     (true, last_agent_message.clone())
@@ -259,10 +639,24 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 }
             }
 
-            event_processor.process_event(event);
+            match output_format {
+                cli::OutputFormat::Text => event_processor.process_event(event),
+                cli::OutputFormat::Json => match serde_json::to_string(&event) {
+                    Ok(line) => println!("{line}"),
+                    Err(e) => error!("Failed to serialize event as JSON: {e:?}"),
+                },
+            }
             if is_last_event {
+                turn_complete_notify.notify_one();
+                // The turn is over; free up the transport for the
+                // post-turn `await_followup` below instead of leaving the
+                // mid-turn watcher polling it.
+                let _ = turn_active_tx.send(false);
                 if !wait_for_followup {
                     handle_last_message(last_assistant_message, last_message_file.as_deref())?;
+                    if let Some(ref logger) = realtime_logger {
+                        logger.shutdown().await?;
+                    }
                     return Ok(());
                 }
                 break;
@@ -271,41 +665,33 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
 
         // If we're in followup mode and assistant responded, wait for supervisor
         if wait_for_followup && assistant_responded {
-            if let Some(ref log_dir) = log_session_dir {
-                let instance_id_str = instance_id
-                    .as_ref()
-                    .map(|s| s.as_str())
-                    .unwrap_or("unknown");
-                match wait_for_supervisor_followup(
-                    log_dir,
-                    instance_id_str,
-                    message_index,
-                    model.as_deref(),
-                )
-                .await
+            if let Some(ref transport) = supervisor_transport {
+                transport
+                    .publish_status(&StatusUpdate {
+                        instance_id: instance_id_str.clone(),
+                        status: "waiting_for_followup".to_string(),
+                        last_message_index: message_index,
+                        timestamp: chrono::Utc::now(),
+                        model: model.clone(),
+                    })
+                    .await?;
+
+                match transport
+                    .await_followup(message_index, tokio::time::Duration::from_secs(300))
+                    .await
                 {
                     Ok(Some(followup)) => {
                         current_prompt = followup;
 
-                        // Update status to indicate we're processing the followup
-                        let status_file = log_dir.join("status.json");
-                        let mut status_obj = serde_json::json!({
-                            "status": "processing",
-                            "instance_id": instance_id_str,
-                            "last_message_index": message_index,
-                            "timestamp": chrono::Utc::now().to_rfc3339()
-                        });
-
-                        // Add model information if available
-                        if let Some(ref model_name) = model {
-                            status_obj["model"] = serde_json::Value::String(model_name.to_string());
-                        }
-
-                        let status = status_obj;
-                        let _ = std::fs::write(
-                            &status_file,
-                            serde_json::to_string_pretty(&status).unwrap_or_default(),
-                        );
+                        transport
+                            .publish_status(&StatusUpdate {
+                                instance_id: instance_id_str.clone(),
+                                status: "processing".to_string(),
+                                last_message_index: message_index,
+                                timestamp: chrono::Utc::now(),
+                                model: model.clone(),
+                            })
+                            .await?;
                         info!("Updated status to 'processing' after receiving followup");
 
                         continue; // Continue the loop with new prompt
@@ -325,6 +711,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
 
+    if let Some(ref logger) = realtime_logger {
+        logger.shutdown().await?;
+    }
+
     Ok(())
 }
 
@@ -350,89 +740,3 @@ fn handle_last_message(
     Ok(())
 }
 
-async fn wait_for_supervisor_followup(
-    log_dir: &std::path::Path,
-    instance_id: &str,
-    message_index: usize,
-    model: Option<&str>,
-) -> anyhow::Result<Option<String>> {
-    use chrono::Utc;
-    use std::fs;
-    use tokio::time::{Duration, sleep};
-
-    let status_file = log_dir.join("status.json");
-    let followup_file = log_dir.join("followup_input.json");
-
-    // Write status to indicate we're waiting for followup
-    let mut status_obj = serde_json::json!({
-        "status": "waiting_for_followup",
-        "instance_id": instance_id,
-        "last_message_index": message_index,
-        "timestamp": Utc::now().to_rfc3339()
-    });
-
-    // Add model information if available
-    if let Some(model_name) = model {
-        status_obj["model"] = serde_json::Value::String(model_name.to_string());
-    }
-
-    let status = status_obj;
-
-    fs::write(&status_file, serde_json::to_string_pretty(&status)?)?;
-    info!("Waiting for supervisor followup...");
-
-    // Poll for followup file with timeout
-    let timeout_duration = Duration::from_secs(300); // 5 minute timeout
-    let start_time = tokio::time::Instant::now();
-
-    loop {
-        // Check if followup file exists
-        if followup_file.exists() {
-            match fs::read_to_string(&followup_file) {
-                Ok(content) => {
-                    // Parse the followup JSON
-                    match serde_json::from_str::<serde_json::Value>(&content) {
-                        Ok(followup_json) => {
-                            // Remove the followup file to prepare for next iteration
-                            let _ = fs::remove_file(&followup_file);
-
-                            if let Some(message) =
-                                followup_json.get("message").and_then(|m| m.as_str())
-                            {
-                                if message.trim().is_empty() {
-                                    // Empty message means terminate
-                                    return Ok(None);
-                                } else {
-                                    // Return the followup message
-                                    return Ok(Some(message.to_string()));
-                                }
-                            } else if followup_json
-                                .get("terminate")
-                                .and_then(|t| t.as_bool())
-                                .unwrap_or(false)
-                            {
-                                // Explicit termination
-                                return Ok(None);
-                            }
-                        }
-                        Err(e) => {
-                            error!("Failed to parse followup JSON: {e:?}");
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to read followup file: {e:?}");
-                }
-            }
-        }
-
-        // Check timeout
-        if start_time.elapsed() > timeout_duration {
-            info!("Timeout waiting for supervisor followup, terminating");
-            return Ok(None);
-        }
-
-        // Sleep before next check
-        sleep(Duration::from_millis(500)).await;
-    }
-}
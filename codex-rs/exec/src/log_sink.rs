@@ -0,0 +1,64 @@
+//! Storage-agnostic sink for realtime-logger records.
+//!
+//! `RealtimeLogger` no longer owns concrete files itself — it builds a
+//! [`LogEntry`] per event and fans it out to whichever sinks were configured
+//! (see [`crate::file_sink::FileSink`], [`crate::postgres_sink::PostgresSink`]),
+//! so a run can log to disk and to Postgres simultaneously.
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+
+/// One logged event, shaped for both the plain-text context file and a
+/// relational sink: `role`/`event_type`/`exit_code`/`token_count` are the
+/// columns a `PostgresSink` indexes on, `value` is the raw event payload.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub instance_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub role: Option<String>,
+    pub event_type: Option<String>,
+    pub exit_code: Option<i32>,
+    pub token_count: Option<i64>,
+    /// Human-readable line appended to `realtime_context.txt` by `FileSink`.
+    pub context_line: String,
+    /// The same value pushed onto `conversation_log`, stored verbatim by
+    /// sinks that want the structured record rather than the text line.
+    pub value: Option<serde_json::Value>,
+}
+
+/// The aggregated result of a completed (or errored) session, as previously
+/// written to `final_result.json`.
+#[derive(Debug, Clone)]
+pub struct FinalResult {
+    pub instance_id: String,
+    pub status: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub conversation: Vec<serde_json::Value>,
+    /// Cumulative token/cost totals from `TokenAccumulator::usage`,
+    /// serialized so sinks don't need to depend on its concrete type.
+    pub usage: serde_json::Value,
+}
+
+/// A destination for realtime-logger records. Implementations decide how
+/// (and whether) to batch; `RealtimeLogger` just awaits `record`/`finalize`
+/// for every configured sink.
+#[async_trait]
+pub trait LogSink: Send + Sync {
+    async fn record(&self, entry: &LogEntry) -> anyhow::Result<()>;
+    async fn finalize(&self, result: &FinalResult) -> anyhow::Result<()>;
+
+    /// Drain any buffered records so everything sent so far has been
+    /// written out (e.g. before `finalize`). Sinks that write synchronously
+    /// can keep the default no-op.
+    async fn flush(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Flush and release any resources (file handles, connection pools).
+    /// Sinks that hold nothing persistent can keep the default no-op.
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
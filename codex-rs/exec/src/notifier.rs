@@ -0,0 +1,111 @@
+//! Fires a compact task-outcome payload at configured observers when a
+//! session reaches `TaskComplete`/`Error`, so supervisors are pushed
+//! outcomes instead of polling `final_result.json`.
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use rand::Rng;
+use tokio::time::Duration;
+use tokio::time::sleep;
+
+const MAX_RETRY_ATTEMPTS: u32 = 5;
+const BASE_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// The payload POSTed (or otherwise delivered) when an instance finishes.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TaskOutcome {
+    pub instance_id: String,
+    pub status: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+    pub last_assistant_message: Option<String>,
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Delivers `outcome`. Implementations are responsible for their own
+    /// retry policy; `notify_all` below wraps transient failures with
+    /// exponential backoff + jitter for `Notifier`s that choose to bubble
+    /// a retryable error up via `Err`.
+    async fn notify(&self, outcome: &TaskOutcome) -> anyhow::Result<()>;
+}
+
+/// POSTs `outcome` as JSON to each configured URL.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    urls: Vec<String>,
+}
+
+impl WebhookNotifier {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            urls,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, outcome: &TaskOutcome) -> anyhow::Result<()> {
+        for url in &self.urls {
+            self.client.post(url).json(outcome).send().await?.error_for_status()?;
+        }
+        Ok(())
+    }
+}
+
+/// Placeholder for a future Slack-specific notifier (richer formatting,
+/// thread replies, etc.) — not yet implemented, but `notify_all` already
+/// treats any `Box<dyn Notifier>` the same way so adding it is additive.
+pub struct SlackNotifier;
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    async fn notify(&self, _outcome: &TaskOutcome) -> anyhow::Result<()> {
+        anyhow::bail!("SlackNotifier is not implemented yet")
+    }
+}
+
+/// Delivers `outcome` to every notifier, retrying each with jittered
+/// exponential backoff so a transient 5xx/network blip doesn't lose the
+/// notification. Logs (rather than propagates) final failures, since a
+/// broken webhook shouldn't fail the run itself.
+pub async fn notify_all(notifiers: &[Box<dyn Notifier>], outcome: &TaskOutcome) {
+    for notifier in notifiers {
+        let mut attempt = 0;
+        loop {
+            match notifier.notify(outcome).await {
+                Ok(()) => break,
+                Err(e) if attempt + 1 >= MAX_RETRY_ATTEMPTS => {
+                    tracing::error!(
+                        "Notifier failed after {} attempts, giving up: {e:?}",
+                        attempt + 1
+                    );
+                    break;
+                }
+                Err(e) => {
+                    let backoff = backoff_with_jitter(attempt);
+                    tracing::warn!(
+                        "Notifier attempt {} failed ({e:?}), retrying in {backoff:?}",
+                        attempt + 1
+                    );
+                    sleep(backoff).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+fn backoff_with_jitter(attempt: u32) -> Duration {
+    let base = BASE_BACKOFF * 2u32.saturating_pow(attempt);
+    let capped = base.min(MAX_BACKOFF);
+    let jitter_ms = rand::thread_rng().gen_range(0..=capped.as_millis() as u64 / 2);
+    capped + Duration::from_millis(jitter_ms)
+}
@@ -0,0 +1,90 @@
+//! Postgres-backed [`LogSink`] so supervisors can query across many
+//! instances ("all failed commands in the last hour") instead of grepping
+//! per-instance text files.
+
+use async_trait::async_trait;
+use bb8::Pool;
+use bb8_postgres::PostgresConnectionManager;
+use tokio_postgres::NoTls;
+
+use crate::log_sink::FinalResult;
+use crate::log_sink::LogEntry;
+use crate::log_sink::LogSink;
+
+/// `CREATE TABLE realtime_log_entries (
+///     instance_id text not null,
+///     ts timestamptz not null,
+///     role text,
+///     event_type text,
+///     exit_code integer,
+///     token_count bigint,
+///     payload jsonb not null
+/// );`
+/// `CREATE TABLE realtime_log_sessions (
+///     instance_id text primary key,
+///     status text not null,
+///     started_at timestamptz not null,
+///     completed_at timestamptz not null,
+///     conversation jsonb not null,
+///     usage jsonb not null default '{}'
+/// );`
+pub struct PostgresSink {
+    pool: Pool<PostgresConnectionManager<NoTls>>,
+}
+
+impl PostgresSink {
+    pub async fn connect(config: &str) -> anyhow::Result<Self> {
+        let manager = PostgresConnectionManager::new_from_stringlike(config, NoTls)?;
+        let pool = Pool::builder().build(manager).await?;
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl LogSink for PostgresSink {
+    async fn record(&self, entry: &LogEntry) -> anyhow::Result<()> {
+        let conn = self.pool.get().await?;
+        let payload = entry.value.clone().unwrap_or(serde_json::Value::Null);
+        conn.execute(
+            "INSERT INTO realtime_log_entries \
+                (instance_id, ts, role, event_type, exit_code, token_count, payload) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            &[
+                &entry.instance_id,
+                &entry.timestamp,
+                &entry.role,
+                &entry.event_type,
+                &entry.exit_code,
+                &entry.token_count,
+                &payload,
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn finalize(&self, result: &FinalResult) -> anyhow::Result<()> {
+        let conn = self.pool.get().await?;
+        let conversation = serde_json::Value::Array(result.conversation.clone());
+        conn.execute(
+            "INSERT INTO realtime_log_sessions \
+                (instance_id, status, started_at, completed_at, conversation, usage) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             ON CONFLICT (instance_id) DO UPDATE SET \
+                status = excluded.status, \
+                completed_at = excluded.completed_at, \
+                conversation = excluded.conversation, \
+                usage = excluded.usage",
+            &[
+                &result.instance_id,
+                &result.status,
+                &result.started_at,
+                &result.completed_at,
+                &conversation,
+                &result.usage,
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+}
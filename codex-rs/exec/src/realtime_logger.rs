@@ -1,50 +1,103 @@
 use codex_core::protocol::{Event, EventMsg};
 use serde_json;
-use std::fs::OpenOptions;
-use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tokio::sync::Mutex;
 use chrono::{DateTime, Utc};
 
-/// Logger that writes events to files in real-time for supervisor monitoring
+use crate::file_sink::FileSink;
+use crate::log_sink::FinalResult;
+use crate::log_sink::LogEntry;
+use crate::log_sink::LogSink;
+use crate::notifier::Notifier;
+use crate::notifier::TaskOutcome;
+use crate::notifier::notify_all;
+use crate::token_accumulator::Budget;
+use crate::token_accumulator::ModelPrice;
+use crate::token_accumulator::TokenAccumulator;
+
+/// Number of events buffered per SSE subscriber before a slow supervisor
+/// starts missing frames (it gets a "missed N events" notice instead of
+/// stalling the broadcast for everyone else).
+const EVENT_BROADCAST_CAPACITY: usize = 256;
+
+/// Logger that fans every event out to a configurable set of [`LogSink`]s
+/// (disk, Postgres, ...) for supervisor monitoring, plus a live SSE feed.
 pub struct RealtimeLogger {
     log_dir: PathBuf,
     instance_id: String,
     conversation_log: Arc<Mutex<Vec<serde_json::Value>>>,
-    context_file: Arc<Mutex<std::fs::File>>,
-    json_file: Arc<Mutex<std::fs::File>>,
     start_time: DateTime<Utc>,
+    /// Fan-out of every logged event as a JSON string (the raw event plus
+    /// `instance_id`, `timestamp` and a `terminal` flag), for
+    /// `sse::events_handler` subscribers.
+    event_tx: broadcast::Sender<String>,
+    sinks: Vec<Box<dyn LogSink>>,
+    /// Fired after `save_final_result` succeeds, so downstream supervisors
+    /// are pushed the outcome instead of polling `final_result.json`.
+    notifiers: Vec<Box<dyn Notifier>>,
+    token_accumulator: TokenAccumulator,
 }
 
 impl RealtimeLogger {
+    /// Convenience constructor matching the original behavior: a single
+    /// [`FileSink`] writing `realtime_context.txt`/`realtime_conversation.json`
+    /// under `log_dir`.
     pub fn new(log_dir: PathBuf, instance_id: String, initial_prompt: &str) -> anyhow::Result<Self> {
-        // Create log directory
-        std::fs::create_dir_all(&log_dir)?;
-        
-        // Create log files
-        let context_path = log_dir.join("realtime_context.txt");
-        let json_path = log_dir.join("realtime_conversation.json");
-        
-        let context_file = Arc::new(Mutex::new(
-            OpenOptions::new()
-                .create(true)
-                .write(true)
-                .truncate(true)
-                .open(&context_path)?
-        ));
-        
-        let json_file = Arc::new(Mutex::new(
-            OpenOptions::new()
-                .create(true)
-                .write(true)
-                .truncate(true)
-                .open(&json_path)?
-        ));
-        
+        let file_sink = FileSink::new(&log_dir, &instance_id, initial_prompt)?;
+        Self::with_sinks(log_dir, instance_id, initial_prompt, vec![Box::new(file_sink)])
+    }
+
+    /// Builds a logger that fans each event out to every sink in `sinks`,
+    /// e.g. a `FileSink` and a `PostgresSink` run side by side so a run's
+    /// events land in both places.
+    pub fn with_sinks(
+        log_dir: PathBuf,
+        instance_id: String,
+        initial_prompt: &str,
+        sinks: Vec<Box<dyn LogSink>>,
+    ) -> anyhow::Result<Self> {
+        Self::with_sinks_and_notifiers(log_dir, instance_id, initial_prompt, sinks, Vec::new())
+    }
+
+    /// Like [`RealtimeLogger::with_sinks`], additionally firing `notifiers`
+    /// once the instance reaches `TaskComplete`/`Error`.
+    pub fn with_sinks_and_notifiers(
+        log_dir: PathBuf,
+        instance_id: String,
+        initial_prompt: &str,
+        sinks: Vec<Box<dyn LogSink>>,
+        notifiers: Vec<Box<dyn Notifier>>,
+    ) -> anyhow::Result<Self> {
+        Self::with_all(
+            log_dir,
+            instance_id,
+            initial_prompt,
+            sinks,
+            notifiers,
+            ModelPrice {
+                input_per_1k: 0.0,
+                output_per_1k: 0.0,
+            },
+            Budget::default(),
+        )
+    }
+
+    /// Full constructor: sinks, notifiers, the per-1K-token price used for
+    /// the `usage` object in `final_result.json`, and an optional spend
+    /// `Budget` that aborts the run via `subscribe_budget_exceeded`.
+    pub fn with_all(
+        log_dir: PathBuf,
+        instance_id: String,
+        initial_prompt: &str,
+        sinks: Vec<Box<dyn LogSink>>,
+        notifiers: Vec<Box<dyn Notifier>>,
+        price: ModelPrice,
+        budget: Budget,
+    ) -> anyhow::Result<Self> {
         let start_time = Utc::now();
-        
-        // Initialize conversation with user prompt
+
         let conversation_log = Arc::new(Mutex::new(vec![
             serde_json::json!({
                 "role": "user",
@@ -52,278 +105,396 @@ impl RealtimeLogger {
                 "timestamp": start_time.to_rfc3339()
             })
         ]));
-        
-        // Write initial context synchronously before creating logger
-        {
-            let mut file = context_file.clone();
-            let mut guard = file.try_lock().unwrap();
-            guard.write_all(format!(
-                "=== CODEX INSTANCE: {} ===\nStarted: {}\nTask: {}\n\n",
-                instance_id,
-                start_time.format("%Y-%m-%d %H:%M:%S UTC"),
-                initial_prompt
-            ).as_bytes())?;
-            guard.flush()?;
-        }
-        
-        let logger = Self {
+
+        let (event_tx, _rx) = broadcast::channel(EVENT_BROADCAST_CAPACITY);
+
+        Ok(Self {
             log_dir,
-            instance_id: instance_id.clone(),
-            conversation_log: conversation_log.clone(),
-            context_file,
-            json_file: json_file.clone(),
+            instance_id,
+            conversation_log,
             start_time,
-        };
-        
-        // Write initial JSON - defer to first log_event call to avoid blocking in sync context
-        
-        Ok(logger)
+            event_tx,
+            sinks,
+            notifiers,
+            token_accumulator: TokenAccumulator::new(price, budget),
+        })
+    }
+
+    /// Subscribe to the budget-exceeded signal so the caller (typically
+    /// `run_main`'s event loop) can abort the run once cumulative spend or
+    /// token usage crosses the configured `Budget`.
+    pub fn subscribe_budget_exceeded(&self) -> tokio::sync::watch::Receiver<bool> {
+        self.token_accumulator.subscribe_budget_exceeded()
+    }
+
+    /// Drain every sink's queue so everything sent so far has been written
+    /// out (e.g. before `save_final_result`).
+    pub async fn flush(&self) -> anyhow::Result<()> {
+        for sink in &self.sinks {
+            sink.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Flush, then release every sink's resources. After this returns no
+    /// further `log_event` calls should be made.
+    pub async fn shutdown(&self) -> anyhow::Result<()> {
+        for sink in &self.sinks {
+            sink.shutdown().await?;
+        }
+        Ok(())
+    }
+
+    /// Subscribe to the live event feed. Used by the SSE handler in
+    /// [`crate::sse`] so a newly connecting supervisor gets a live tail
+    /// instead of having to poll `realtime_context.txt`.
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.event_tx.subscribe()
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    pub fn log_dir(&self) -> &std::path::Path {
+        &self.log_dir
     }
-    
+
+    /// Handles one event: builds a [`LogEntry`] for it, then hands that
+    /// entry to every configured sink. `FileSink::record` is a cheap
+    /// non-blocking `send` to its own writer task, so a burst of
+    /// `ExecCommandEnd` events with large stdout no longer serializes
+    /// behind file I/O; a `PostgresSink` does its insert inline.
     pub async fn log_event(&self, event: &Event) -> anyhow::Result<()> {
         let timestamp = Utc::now();
-        
-        // Skip initial JSON write - we'll only write final result at completion
-        
+
+        // Push the raw event onto the broadcast channel first so SSE
+        // subscribers see it as soon as it's logged, regardless of which
+        // arm below handles it. A `send` error just means there are no
+        // subscribers right now, which is fine. `terminal` lets
+        // `sse::events_handler` close the stream on this event without
+        // having to guess at `EventMsg`'s own serde representation.
+        let terminal = matches!(&event.msg, EventMsg::TaskComplete(_) | EventMsg::Error(_));
+        if let Ok(serialized) = serde_json::to_string(&serde_json::json!({
+            "instance_id": self.instance_id,
+            "timestamp": timestamp.to_rfc3339(),
+            "terminal": terminal,
+            "event": event,
+        })) {
+            let _ = self.event_tx.send(serialized);
+        }
+
+        let mut entry = LogEntry {
+            instance_id: self.instance_id.clone(),
+            timestamp,
+            role: None,
+            event_type: None,
+            exit_code: None,
+            token_count: None,
+            context_line: String::new(),
+            value: None,
+        };
+
         match &event.msg {
             EventMsg::AgentMessage(msg) => {
-                // Add to conversation log
-                {
-                    let mut log = self.conversation_log.lock().await;
-                    log.push(serde_json::json!({
-                        "role": "assistant",
-                        "content": msg.message,
-                        "timestamp": timestamp.to_rfc3339()
-                    }));
-                }
-                
-                // Append to context
-                self.append_context(&format!(
+                entry.role = Some("assistant".to_string());
+                entry.value = Some(serde_json::json!({
+                    "role": "assistant",
+                    "content": msg.message,
+                    "timestamp": timestamp.to_rfc3339()
+                }));
+                entry.context_line = format!(
                     "[{}] ASSISTANT: {}\n",
                     timestamp.format("%H:%M:%S"),
                     msg.message
-                )).await?;
-            },
-            
+                );
+            }
+
             EventMsg::ExecCommandBegin(cmd) => {
-                // Add to conversation log
-                {
-                    let mut log = self.conversation_log.lock().await;
-                    log.push(serde_json::json!({
-                        "role": "system",
-                        "content": format!("Executing command: {:?}", cmd.command),
-                        "timestamp": timestamp.to_rfc3339(),
-                        "event_type": "exec_command_begin"
-                    }));
-                }
-                
-                self.append_context(&format!(
+                entry.role = Some("system".to_string());
+                entry.event_type = Some("exec_command_begin".to_string());
+                entry.value = Some(serde_json::json!({
+                    "role": "system",
+                    "content": format!("Executing command: {:?}", cmd.command),
+                    "timestamp": timestamp.to_rfc3339(),
+                    "event_type": "exec_command_begin"
+                }));
+                entry.context_line = format!(
                     "[{}] EXECUTING: {:?}\n",
                     timestamp.format("%H:%M:%S"),
                     cmd.command
-                )).await?;
-            },
-            
+                );
+            }
+
             EventMsg::ExecCommandEnd(result) => {
                 let status = if result.exit_code == 0 { "✅" } else { "❌" };
-                
-                // Add to conversation log
-                {
-                    let mut log = self.conversation_log.lock().await;
-                    let mut content = format!("Command completed with exit code {}", result.exit_code);
-                    
-                    if !result.stdout.is_empty() {
-                        content.push_str(&format!("\nSTDOUT: {}", result.stdout));
-                    }
-                    
-                    if !result.stderr.is_empty() {
-                        content.push_str(&format!("\nSTDERR: {}", result.stderr));
-                    }
-                    
-                    log.push(serde_json::json!({
-                        "role": "system",
-                        "content": content,
-                        "timestamp": timestamp.to_rfc3339(),
-                        "event_type": "exec_command_end",
-                        "exit_code": result.exit_code
-                    }));
+                let mut content = format!("Command completed with exit code {}", result.exit_code);
+
+                if !result.stdout.is_empty() {
+                    content.push_str(&format!("\nSTDOUT: {}", result.stdout));
                 }
-                
-                self.append_context(&format!(
+                if !result.stderr.is_empty() {
+                    content.push_str(&format!("\nSTDERR: {}", result.stderr));
+                }
+
+                let mut line = format!(
                     "[{}] COMMAND RESULT {}: Exit code {}\n",
                     timestamp.format("%H:%M:%S"),
                     status,
                     result.exit_code
-                )).await?;
-                
+                );
                 if !result.stdout.is_empty() {
                     let preview = if result.stdout.len() > 500 {
                         format!("{}... (truncated)", &result.stdout[..500])
                     } else {
                         result.stdout.clone()
                     };
-                    self.append_context(&format!("STDOUT: {}\n", preview)).await?;
+                    line.push_str(&format!("STDOUT: {}\n", preview));
                 }
-                
                 if !result.stderr.is_empty() {
                     let preview = if result.stderr.len() > 500 {
                         format!("{}... (truncated)", &result.stderr[..500])
                     } else {
                         result.stderr.clone()
                     };
-                    self.append_context(&format!("STDERR: {}\n", preview)).await?;
+                    line.push_str(&format!("STDERR: {}\n", preview));
                 }
-            },
-            
+
+                entry.role = Some("system".to_string());
+                entry.event_type = Some("exec_command_end".to_string());
+                entry.exit_code = Some(result.exit_code);
+                entry.value = Some(serde_json::json!({
+                    "role": "system",
+                    "content": content,
+                    "timestamp": timestamp.to_rfc3339(),
+                    "event_type": "exec_command_end",
+                    "exit_code": result.exit_code
+                }));
+                entry.context_line = line;
+            }
+
             EventMsg::McpToolCallBegin(tool) => {
-                // Add to conversation log
-                {
-                    let mut log = self.conversation_log.lock().await;
-                    log.push(serde_json::json!({
-                        "role": "system",
-                        "content": format!("Tool call: {} ({})", tool.tool, tool.call_id),
-                        "timestamp": timestamp.to_rfc3339(),
-                        "event_type": "tool_call_begin",
-                        "tool_name": tool.tool,
-                        "call_id": tool.call_id
-                    }));
-                }
-                
-                self.append_context(&format!(
+                entry.role = Some("system".to_string());
+                entry.event_type = Some("tool_call_begin".to_string());
+                entry.value = Some(serde_json::json!({
+                    "role": "system",
+                    "content": format!("Tool call: {} ({})", tool.tool, tool.call_id),
+                    "timestamp": timestamp.to_rfc3339(),
+                    "event_type": "tool_call_begin",
+                    "tool_name": tool.tool,
+                    "call_id": tool.call_id
+                }));
+                entry.context_line = format!(
                     "[{}] TOOL CALL: {} ({})\n",
                     timestamp.format("%H:%M:%S"),
                     tool.tool,
                     tool.call_id
-                )).await?;
-            },
-            
+                );
+            }
+
             EventMsg::McpToolCallEnd(result) => {
                 let status = if result.result.is_ok() { "✅" } else { "❌" };
-                
-                // Add to conversation log
-                {
-                    let mut log = self.conversation_log.lock().await;
-                    let content = match &result.result {
-                        Ok(output) => format!("Tool call completed: {:?}", output),
-                        Err(error) => format!("Tool call failed: {:?}", error)
-                    };
-                    
-                    log.push(serde_json::json!({
-                        "role": "system",
-                        "content": content,
-                        "timestamp": timestamp.to_rfc3339(),
-                        "event_type": "tool_call_end",
-                        "call_id": result.call_id,
-                        "success": result.result.is_ok()
-                    }));
-                }
-                
-                self.append_context(&format!(
+                let content = match &result.result {
+                    Ok(output) => format!("Tool call completed: {:?}", output),
+                    Err(error) => format!("Tool call failed: {:?}", error),
+                };
+
+                entry.role = Some("system".to_string());
+                entry.event_type = Some("tool_call_end".to_string());
+                entry.value = Some(serde_json::json!({
+                    "role": "system",
+                    "content": content,
+                    "timestamp": timestamp.to_rfc3339(),
+                    "event_type": "tool_call_end",
+                    "call_id": result.call_id,
+                    "success": result.result.is_ok()
+                }));
+                entry.context_line = format!(
                     "[{}] TOOL RESULT {}: {}\n",
                     timestamp.format("%H:%M:%S"),
                     status,
                     result.call_id
-                )).await?;
-            },
-            
+                );
+            }
+
             EventMsg::TaskComplete(_) => {
-                self.append_context(&format!(
-                    "[{}] ✅ TASK COMPLETED\n",
-                    timestamp.format("%H:%M:%S")
-                )).await?;
-                
-                // Save final result
-                self.save_final_result("completed").await?;
-            },
-            
+                entry.event_type = Some("task_complete".to_string());
+                entry.context_line =
+                    format!("[{}] ✅ TASK COMPLETED\n", timestamp.format("%H:%M:%S"));
+            }
+
             EventMsg::Error(err) => {
-                self.append_context(&format!(
+                entry.event_type = Some("error".to_string());
+                entry.context_line = format!(
                     "[{}] ❌ ERROR: {}\n",
                     timestamp.format("%H:%M:%S"),
                     err.message
-                )).await?;
-                
-                // Save final result with error
-                self.save_final_result("error").await?;
-            },
-            
-            // Handle specific events we want in the JSON conversation log
+                );
+            }
+
             EventMsg::TokenCount(usage) => {
-                // Add to conversation log
-                {
-                    let mut log = self.conversation_log.lock().await;
-                    log.push(serde_json::json!({
+                entry.role = Some("system".to_string());
+                entry.event_type = Some("token_count".to_string());
+                entry.token_count = Some(usage.total_tokens as i64);
+                entry.value = Some(serde_json::json!({
+                    "role": "system",
+                    "content": format!("Token usage - Input: {}, Output: {}, Total: {}",
+                                     usage.input_tokens, usage.output_tokens, usage.total_tokens),
+                    "timestamp": timestamp.to_rfc3339(),
+                    "event_type": "token_count",
+                    "input_tokens": usage.input_tokens,
+                    "output_tokens": usage.output_tokens,
+                    "total_tokens": usage.total_tokens
+                }));
+                entry.context_line =
+                    format!("[{}] EVENT: {:?}\n", timestamp.format("%H:%M:%S"), event.msg);
+
+                if let Some(exceeded) = self.token_accumulator.record(
+                    usage.input_tokens,
+                    usage.output_tokens,
+                    usage.total_tokens,
+                ) {
+                    let budget_entry = serde_json::json!({
                         "role": "system",
-                        "content": format!("Token usage - Input: {}, Output: {}, Total: {}", 
-                                         usage.input_tokens, usage.output_tokens, usage.total_tokens),
+                        "content": format!(
+                            "Budget exceeded: {} total tokens, ${:.4} estimated cost",
+                            exceeded.total_tokens, exceeded.estimated_cost_usd
+                        ),
                         "timestamp": timestamp.to_rfc3339(),
-                        "event_type": "token_count",
-                        "input_tokens": usage.input_tokens,
-                        "output_tokens": usage.output_tokens,
-                        "total_tokens": usage.total_tokens
-                    }));
+                        "event_type": "budget_exceeded",
+                    });
+                    self.conversation_log.lock().await.push(budget_entry);
+                    let budget_record = LogEntry {
+                        instance_id: self.instance_id.clone(),
+                        timestamp,
+                        role: Some("system".to_string()),
+                        event_type: Some("budget_exceeded".to_string()),
+                        exit_code: None,
+                        token_count: Some(exceeded.total_tokens as i64),
+                        context_line: format!(
+                            "[{}] ⚠️ BUDGET EXCEEDED: {} total tokens, ${:.4} estimated cost\n",
+                            timestamp.format("%H:%M:%S"),
+                            exceeded.total_tokens,
+                            exceeded.estimated_cost_usd
+                        ),
+                        value: None,
+                    };
+                    for sink in &self.sinks {
+                        sink.record(&budget_record).await?;
+                    }
                 }
-                
-                self.append_context(&format!(
-                    "[{}] EVENT: {:?}\n",
-                    timestamp.format("%H:%M:%S"),
-                    event.msg
-                )).await?;
-                
-            },
-            
+            }
+
             EventMsg::AgentReasoning(reasoning) => {
-                // Add to conversation log
-                {
-                    let mut log = self.conversation_log.lock().await;
-                    log.push(serde_json::json!({
-                        "role": "system",
-                        "content": format!("Agent reasoning: {}", reasoning.text),
-                        "timestamp": timestamp.to_rfc3339(),
-                        "event_type": "agent_reasoning"
-                    }));
-                }
-                
-                self.append_context(&format!(
-                    "[{}] EVENT: {:?}\n",
-                    timestamp.format("%H:%M:%S"),
-                    event.msg
-                )).await?;
-                
-            },
-            
+                entry.role = Some("system".to_string());
+                entry.event_type = Some("agent_reasoning".to_string());
+                entry.value = Some(serde_json::json!({
+                    "role": "system",
+                    "content": format!("Agent reasoning: {}", reasoning.text),
+                    "timestamp": timestamp.to_rfc3339(),
+                    "event_type": "agent_reasoning"
+                }));
+                entry.context_line =
+                    format!("[{}] EVENT: {:?}\n", timestamp.format("%H:%M:%S"), event.msg);
+            }
+
             _ => {
-                // Log other events in a generic way (context only, not JSON)
-                self.append_context(&format!(
-                    "[{}] EVENT: {:?}\n",
-                    timestamp.format("%H:%M:%S"),
-                    event.msg
-                )).await?;
+                entry.context_line =
+                    format!("[{}] EVENT: {:?}\n", timestamp.format("%H:%M:%S"), event.msg);
             }
         }
-        
-        Ok(())
-    }
-    
-    async fn append_context(&self, text: &str) -> anyhow::Result<()> {
-        let mut file = self.context_file.lock().await;
-        file.write_all(text.as_bytes())?;
-        file.flush()?;
+
+        // `TaskComplete`/`Error` additionally write a final, aggregated
+        // result once the entry above has landed.
+        let needs_final_result = matches!(
+            &event.msg,
+            EventMsg::TaskComplete(_) | EventMsg::Error(_)
+        );
+
+        if let Some(ref value) = entry.value {
+            self.conversation_log.lock().await.push(value.clone());
+        }
+
+        for sink in &self.sinks {
+            sink.record(&entry).await?;
+        }
+
+        if needs_final_result {
+            // Make sure every sink has applied everything queued above
+            // before the final result is written.
+            self.flush().await?;
+            let status = if matches!(&event.msg, EventMsg::Error(_)) {
+                "error"
+            } else {
+                "completed"
+            };
+            self.save_final_result(status).await?;
+        }
+
         Ok(())
     }
-    
+
     async fn save_final_result(&self, status: &str) -> anyhow::Result<()> {
-        let final_result = serde_json::json!({
-            "instance_id": self.instance_id,
-            "status": status,
-            "started_at": self.start_time.to_rfc3339(),
-            "completed_at": Utc::now().to_rfc3339(),
-            "conversation": *self.conversation_log.lock().await
-        });
-        
-        let result_path = self.log_dir.join("final_result.json");
-        tokio::fs::write(&result_path, serde_json::to_string_pretty(&final_result)?).await?;
-        
+        let conversation = self.conversation_log.lock().await.clone();
+        let result = FinalResult {
+            instance_id: self.instance_id.clone(),
+            status: status.to_string(),
+            started_at: self.start_time,
+            completed_at: Utc::now(),
+            conversation: conversation.clone(),
+            usage: serde_json::to_value(self.token_accumulator.usage()).unwrap_or_default(),
+        };
+
+        for sink in &self.sinks {
+            sink.finalize(&result).await?;
+        }
+
+        if !self.notifiers.is_empty() {
+            let (input_tokens, output_tokens, total_tokens) = last_token_totals(&conversation);
+            let outcome = TaskOutcome {
+                instance_id: self.instance_id.clone(),
+                status: status.to_string(),
+                started_at: self.start_time,
+                completed_at: result.completed_at,
+                input_tokens,
+                output_tokens,
+                total_tokens,
+                last_assistant_message: last_assistant_message(&conversation),
+            };
+            notify_all(&self.notifiers, &outcome).await;
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Path `sse::final_result_handler` reads once the instance reaches
+    /// `TaskComplete`/`Error`. Only meaningful when a `FileSink` is
+    /// configured; other sinks finalize wherever they store results.
+    pub fn final_result_path(&self) -> PathBuf {
+        self.log_dir.join("final_result.json")
+    }
+}
+
+fn last_token_totals(conversation: &[serde_json::Value]) -> (u64, u64, u64) {
+    conversation
+        .iter()
+        .rev()
+        .find(|entry| entry.get("event_type").and_then(|t| t.as_str()) == Some("token_count"))
+        .map(|entry| {
+            (
+                entry.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+                entry.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+                entry.get("total_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+            )
+        })
+        .unwrap_or((0, 0, 0))
+}
+
+fn last_assistant_message(conversation: &[serde_json::Value]) -> Option<String> {
+    conversation
+        .iter()
+        .rev()
+        .find(|entry| entry.get("role").and_then(|r| r.as_str()) == Some("assistant"))
+        .and_then(|entry| entry.get("content").and_then(|c| c.as_str()))
+        .map(|s| s.to_string())
+}
@@ -0,0 +1,285 @@
+//! Redis pub/sub [`SupervisorTransport`] for supervisors that coordinate
+//! instances across hosts, where a shared log directory isn't available.
+//! Speaks just enough RESP to `PUBLISH` status and `SUBSCRIBE` for a
+//! followup, via a hand-rolled client rather than pulling in a full Redis
+//! crate for two commands.
+
+use async_trait::async_trait;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::Duration;
+
+use crate::supervisor_transport::StatusUpdate;
+use crate::supervisor_transport::SupervisorTransport;
+
+/// A single RESP frame, wide enough to represent replies to `PUBLISH` and
+/// the `message` arrays `SUBSCRIBE` emits.
+#[derive(Debug, Clone)]
+pub enum RespValue {
+    /// Covers `+` simple strings, `-` errors, and `:` integers, which are
+    /// all "read one line" frames as far as this minimal client cares.
+    Line(String),
+    Bulk(Option<Vec<u8>>),
+    Array(Vec<RespValue>),
+}
+
+/// Result of trying to parse one frame out of a buffer that may end
+/// mid-frame because it's filled directly from socket reads.
+enum RespParse {
+    Complete { value: RespValue, consumed: usize },
+    Incomplete,
+}
+
+/// Parses at most one RESP frame from the start of `buf`. Returns
+/// `Incomplete` rather than erroring when `buf` doesn't yet contain a full
+/// frame, so the caller can read more bytes and retry instead of treating a
+/// short read as malformed input.
+fn parse_resp(buf: &[u8]) -> anyhow::Result<RespParse> {
+    if buf.is_empty() {
+        return Ok(RespParse::Incomplete);
+    }
+
+    let Some(line_end) = find_crlf(buf) else {
+        return Ok(RespParse::Incomplete);
+    };
+
+    match buf[0] {
+        b'+' | b'-' | b':' => {
+            let line = std::str::from_utf8(&buf[1..line_end])?.to_string();
+            Ok(RespParse::Complete {
+                value: RespValue::Line(line),
+                consumed: line_end + 2,
+            })
+        }
+        b'$' => {
+            let len: i64 = std::str::from_utf8(&buf[1..line_end])?.parse()?;
+            if len < 0 {
+                return Ok(RespParse::Complete {
+                    value: RespValue::Bulk(None),
+                    consumed: line_end + 2,
+                });
+            }
+            let body_start = line_end + 2;
+            let body_end = body_start + len as usize;
+            // +2 for the trailing CRLF after the bulk payload.
+            if buf.len() < body_end + 2 {
+                return Ok(RespParse::Incomplete);
+            }
+            Ok(RespParse::Complete {
+                value: RespValue::Bulk(Some(buf[body_start..body_end].to_vec())),
+                consumed: body_end + 2,
+            })
+        }
+        b'*' => {
+            let count: i64 = std::str::from_utf8(&buf[1..line_end])?.parse()?;
+            let mut offset = line_end + 2;
+            let mut items = Vec::new();
+            for _ in 0..count.max(0) {
+                match parse_resp(&buf[offset..])? {
+                    RespParse::Complete { value, consumed } => {
+                        items.push(value);
+                        offset += consumed;
+                    }
+                    RespParse::Incomplete => return Ok(RespParse::Incomplete),
+                }
+            }
+            Ok(RespParse::Complete {
+                value: RespValue::Array(items),
+                consumed: offset,
+            })
+        }
+        other => anyhow::bail!("Unsupported RESP type byte: {other}"),
+    }
+}
+
+fn find_crlf(buf: &[u8]) -> Option<usize> {
+    buf.windows(2).position(|w| w == b"\r\n")
+}
+
+fn encode_command(parts: &[&[u8]]) -> Vec<u8> {
+    let mut out = format!("*{}\r\n", parts.len()).into_bytes();
+    for part in parts {
+        out.extend_from_slice(format!("${}\r\n", part.len()).as_bytes());
+        out.extend_from_slice(part);
+        out.extend_from_slice(b"\r\n");
+    }
+    out
+}
+
+/// Reads from `stream` until `parse_resp` returns a complete frame,
+/// accumulating into `buf` across reads that land mid-frame.
+async fn read_frame(stream: &mut TcpStream, buf: &mut Vec<u8>) -> anyhow::Result<RespValue> {
+    loop {
+        if let RespParse::Complete { value, consumed } = parse_resp(buf)? {
+            buf.drain(..consumed);
+            return Ok(value);
+        }
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            anyhow::bail!("Redis connection closed mid-frame");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+pub struct RedisTransport {
+    addr: String,
+    instance_id: String,
+}
+
+impl RedisTransport {
+    /// `addr` is a `redis://host:port` URL; `instance_id` scopes the
+    /// per-instance status/followup channels.
+    pub async fn connect(addr: &str, instance_id: &str) -> anyhow::Result<Self> {
+        // Fail fast on an address we can't resolve, rather than deferring
+        // the error to the first `publish_status` call.
+        let host_port = addr
+            .strip_prefix("redis://")
+            .ok_or_else(|| anyhow::anyhow!("Expected a redis:// URL, got {addr:?}"))?;
+        TcpStream::connect(host_port).await?;
+        Ok(Self {
+            addr: host_port.to_string(),
+            instance_id: instance_id.to_string(),
+        })
+    }
+
+    fn status_channel(&self) -> String {
+        format!("artemis:{}:status", self.instance_id)
+    }
+
+    fn followup_channel(&self) -> String {
+        format!("artemis:{}:followup", self.instance_id)
+    }
+}
+
+#[async_trait]
+impl SupervisorTransport for RedisTransport {
+    async fn publish_status(&self, status: &StatusUpdate) -> anyhow::Result<()> {
+        let payload = serde_json::to_vec(status)?;
+        let mut stream = TcpStream::connect(&self.addr).await?;
+        let command = encode_command(&[b"PUBLISH", self.status_channel().as_bytes(), &payload]);
+        stream.write_all(&command).await?;
+        let mut buf = Vec::new();
+        read_frame(&mut stream, &mut buf).await?;
+        Ok(())
+    }
+
+    async fn await_followup(
+        &self,
+        _message_index: usize,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<String>> {
+        let mut stream = TcpStream::connect(&self.addr).await?;
+        let subscribe = encode_command(&[b"SUBSCRIBE", self.followup_channel().as_bytes()]);
+        stream.write_all(&subscribe).await?;
+
+        let mut buf = Vec::new();
+        // First frame back is the subscribe confirmation, not a message.
+        read_frame(&mut stream, &mut buf).await?;
+
+        let message = tokio::time::timeout(timeout, async {
+            loop {
+                if let RespValue::Array(items) = read_frame(&mut stream, &mut buf).await? {
+                    if let [RespValue::Bulk(Some(kind)), _channel, RespValue::Bulk(Some(payload))] =
+                        items.as_slice()
+                    {
+                        if kind == b"message" {
+                            return Ok::<Vec<u8>, anyhow::Error>(payload.clone());
+                        }
+                    }
+                }
+            }
+        })
+        .await;
+
+        let payload = match message {
+            Ok(Ok(payload)) => payload,
+            Ok(Err(e)) => return Err(e),
+            Err(_) => return Ok(None), // timed out
+        };
+
+        let followup_json: serde_json::Value = serde_json::from_slice(&payload)?;
+        if let Some(text) = followup_json.get("message").and_then(|m| m.as_str()) {
+            if text.trim().is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(text.to_string()))
+            }
+        } else if followup_json
+            .get("terminate")
+            .and_then(|t| t.as_bool())
+            .unwrap_or(false)
+        {
+            Ok(None)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_simple_string() {
+        let RespParse::Complete { value, consumed } = parse_resp(b"+OK\r\n").unwrap() else {
+            panic!("expected a complete frame");
+        };
+        assert!(matches!(value, RespValue::Line(ref s) if s == "OK"));
+        assert_eq!(consumed, 5);
+    }
+
+    #[test]
+    fn bulk_string_split_across_reads_is_incomplete() {
+        // "$5\r\nhello\r\n" with the payload and trailing CRLF cut off --
+        // this is exactly what a single `TcpStream::read` can hand back
+        // mid-frame.
+        let partial = b"$5\r\nhel";
+        assert!(matches!(parse_resp(partial).unwrap(), RespParse::Incomplete));
+
+        let full = b"$5\r\nhello\r\n";
+        let RespParse::Complete { value, consumed } = parse_resp(full).unwrap() else {
+            panic!("expected a complete frame once the full payload has arrived");
+        };
+        assert!(matches!(value, RespValue::Bulk(Some(ref b)) if b == b"hello"));
+        assert_eq!(consumed, full.len());
+    }
+
+    #[test]
+    fn null_bulk_string() {
+        let RespParse::Complete { value, consumed } = parse_resp(b"$-1\r\n").unwrap() else {
+            panic!("expected a complete frame");
+        };
+        assert!(matches!(value, RespValue::Bulk(None)));
+        assert_eq!(consumed, 5);
+    }
+
+    #[test]
+    fn array_of_bulk_strings_like_a_subscribe_message() {
+        let frame = b"*3\r\n$7\r\nmessage\r\n$4\r\nchan\r\n$5\r\nhello\r\n";
+        let RespParse::Complete { value, consumed } = parse_resp(frame).unwrap() else {
+            panic!("expected a complete frame");
+        };
+        let RespValue::Array(items) = value else {
+            panic!("expected an array");
+        };
+        assert_eq!(items.len(), 3);
+        assert!(matches!(&items[0], RespValue::Bulk(Some(b)) if b == b"message"));
+        assert_eq!(consumed, frame.len());
+    }
+
+    #[test]
+    fn array_missing_its_tail_is_incomplete() {
+        // Only the first two of three elements have arrived.
+        let partial = b"*3\r\n$7\r\nmessage\r\n$4\r\nchan\r\n";
+        assert!(matches!(parse_resp(partial).unwrap(), RespParse::Incomplete));
+    }
+
+    #[test]
+    fn unsupported_type_byte_is_an_error() {
+        assert!(parse_resp(b"!oops\r\n").is_err());
+    }
+}
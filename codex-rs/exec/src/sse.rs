@@ -0,0 +1,130 @@
+//! Embedded HTTP server exposing each running instance's event stream as
+//! Server-Sent Events, so a supervisor can watch many concurrent Codex
+//! instances live instead of polling `realtime_context.txt`/
+//! `realtime_conversation.json` on a shared filesystem.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::Router;
+use axum::extract::Path;
+use axum::extract::State;
+use axum::response::Json;
+use axum::response::Sse;
+use axum::response::sse::Event as SseEvent;
+use axum::routing::get;
+use futures::stream::Stream;
+use tokio::sync::Mutex;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::StreamExt;
+
+use crate::realtime_logger::RealtimeLogger;
+
+/// Registry of the instances currently logging through this process, keyed
+/// by `instance_id`. `run_main` registers its logger here so the SSE
+/// routes can find it by the `{instance_id}` path segment.
+#[derive(Clone, Default)]
+pub struct LoggerRegistry {
+    inner: Arc<Mutex<HashMap<String, Arc<RealtimeLogger>>>>,
+}
+
+impl LoggerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn register(&self, logger: Arc<RealtimeLogger>) {
+        self.inner
+            .lock()
+            .await
+            .insert(logger.instance_id().to_string(), logger);
+    }
+
+    pub async fn unregister(&self, instance_id: &str) {
+        self.inner.lock().await.remove(instance_id);
+    }
+
+    async fn get(&self, instance_id: &str) -> Option<Arc<RealtimeLogger>> {
+        self.inner.lock().await.get(instance_id).cloned()
+    }
+}
+
+/// Builds the Axum router supervisors connect to: one SSE route per
+/// instance's live event tail, and one JSON route for the completed result.
+pub fn router(registry: LoggerRegistry) -> Router {
+    Router::new()
+        .route("/instances/:instance_id/events", get(events_handler))
+        .route(
+            "/instances/:instance_id/final_result",
+            get(final_result_handler),
+        )
+        .with_state(registry)
+}
+
+async fn events_handler(
+    State(registry): State<LoggerRegistry>,
+    Path(instance_id): Path<String>,
+) -> Sse<impl Stream<Item = Result<SseEvent, std::convert::Infallible>>> {
+    let logger = registry.get(&instance_id).await;
+
+    let stream = async_stream::stream! {
+        let Some(logger) = logger else {
+            yield Ok(SseEvent::default()
+                .event("error")
+                .data(format!("unknown instance_id {instance_id}")));
+            return;
+        };
+
+        let mut rx = BroadcastStream::new(logger.subscribe());
+        while let Some(item) = rx.next().await {
+            match item {
+                Ok(payload) => {
+                    // `RealtimeLogger::log_event` stamps every payload with
+                    // a `terminal` flag so this handler can close the
+                    // stream on `TaskComplete`/`Error` without having to
+                    // parse `EventMsg` itself.
+                    let is_terminal = serde_json::from_str::<serde_json::Value>(&payload)
+                        .ok()
+                        .and_then(|v| v.get("terminal").and_then(|t| t.as_bool()))
+                        .unwrap_or(false);
+                    yield Ok(SseEvent::default().data(payload));
+                    if is_terminal {
+                        return;
+                    }
+                }
+                Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                    // The subscriber fell behind; rather than close the
+                    // stream (which would strand the supervisor), tell it
+                    // how many frames it missed and keep tailing.
+                    yield Ok(SseEvent::default()
+                        .event("lagged")
+                        .data(format!("missed {missed} events")));
+                }
+            }
+        }
+        // `BroadcastStream` just ends once the sender side (the logger's
+        // `event_tx`) is dropped; there's no separate "closed" error variant
+        // to match on.
+    };
+
+    Sse::new(stream)
+}
+
+async fn final_result_handler(
+    State(registry): State<LoggerRegistry>,
+    Path(instance_id): Path<String>,
+) -> Result<Json<serde_json::Value>, axum::http::StatusCode> {
+    let logger = registry
+        .get(&instance_id)
+        .await
+        .ok_or(axum::http::StatusCode::NOT_FOUND)?;
+
+    let bytes = tokio::fs::read(logger.final_result_path())
+        .await
+        .map_err(|_| axum::http::StatusCode::NOT_FOUND)?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&bytes).map_err(|_| axum::http::StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(value))
+}
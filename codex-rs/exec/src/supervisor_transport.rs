@@ -0,0 +1,38 @@
+//! Abstracts how `run_main`'s followup loop talks to a supervisor, so the
+//! same loop works whether the supervisor shares this host's filesystem
+//! ([`crate::file_transport::FileTransport`]) or is reached over the
+//! network ([`crate::redis_transport::RedisTransport`]).
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Serialize;
+use tokio::time::Duration;
+
+/// Mirrors the `status.json` shape the original file-based handshake wrote,
+/// so `FileTransport::publish_status` is a drop-in replacement.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusUpdate {
+    pub instance_id: String,
+    pub status: String,
+    pub last_message_index: usize,
+    pub timestamp: DateTime<Utc>,
+    pub model: Option<String>,
+}
+
+#[async_trait]
+pub trait SupervisorTransport: Send + Sync {
+    /// Publishes the instance's current status (e.g. `waiting_for_followup`,
+    /// `processing`) for a supervisor to observe.
+    async fn publish_status(&self, status: &StatusUpdate) -> anyhow::Result<()>;
+
+    /// Blocks until a followup arrives or `timeout` elapses. `Ok(None)`
+    /// covers both an explicit supervisor terminate and a timeout; callers
+    /// that need to distinguish the two should watch for the timeout
+    /// themselves before calling this.
+    async fn await_followup(
+        &self,
+        message_index: usize,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<String>>;
+}
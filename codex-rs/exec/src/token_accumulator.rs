@@ -0,0 +1,185 @@
+//! Running token/cost totals for a session, with an optional budget that
+//! can abort the run once cumulative spend crosses a threshold.
+
+use std::sync::Mutex;
+
+use tokio::sync::watch;
+
+/// Per-1K-token USD price for a model. `EventMsg::TokenCount` only reports
+/// cumulative totals, not per-request deltas, so `TokenAccumulator` tracks
+/// the running totals itself and prices the *latest* cumulative snapshot
+/// rather than trying to price individual deltas.
+#[derive(Debug, Clone, Copy)]
+pub struct ModelPrice {
+    pub input_per_1k: f64,
+    pub output_per_1k: f64,
+}
+
+impl ModelPrice {
+    fn estimate(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 / 1000.0) * self.input_per_1k
+            + (output_tokens as f64 / 1000.0) * self.output_per_1k
+    }
+}
+
+/// What to do once cumulative cost or total tokens crosses a threshold.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Budget {
+    pub max_cost_usd: Option<f64>,
+    pub max_total_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+    pub estimated_cost_usd: f64,
+}
+
+/// Tracks cumulative token usage and estimated cost across a session, and
+/// signals `budget_exceeded` (via `subscribe_budget_exceeded`) the first time a configured
+/// budget is crossed.
+pub struct TokenAccumulator {
+    price: ModelPrice,
+    budget: Budget,
+    usage: Mutex<Usage>,
+    budget_exceeded_tx: watch::Sender<bool>,
+}
+
+impl TokenAccumulator {
+    pub fn new(price: ModelPrice, budget: Budget) -> Self {
+        let (budget_exceeded_tx, _rx) = watch::channel(false);
+        Self {
+            price,
+            budget,
+            usage: Mutex::new(Usage::default()),
+            budget_exceeded_tx,
+        }
+    }
+
+    /// Subscribe to the budget-exceeded signal; the supervisor can `.await`
+    /// a `true` value on this to abort the run.
+    pub fn subscribe_budget_exceeded(&self) -> watch::Receiver<bool> {
+        self.budget_exceeded_tx.subscribe()
+    }
+
+    /// Records the latest cumulative totals from an `EventMsg::TokenCount`
+    /// event. Returns `Some(Usage)` the moment a configured budget is
+    /// first crossed (subsequent calls past the threshold return `None`,
+    /// since the signal already fired).
+    pub fn record(&self, input_tokens: u64, output_tokens: u64, total_tokens: u64) -> Option<Usage> {
+        let estimated_cost_usd = self.price.estimate(input_tokens, output_tokens);
+        let usage = Usage {
+            input_tokens,
+            output_tokens,
+            total_tokens,
+            estimated_cost_usd,
+        };
+
+        let mut guard = self.usage.lock().unwrap();
+        *guard = usage;
+        drop(guard);
+
+        let cost_exceeded = self
+            .budget
+            .max_cost_usd
+            .is_some_and(|max| estimated_cost_usd >= max);
+        let tokens_exceeded = self
+            .budget
+            .max_total_tokens
+            .is_some_and(|max| total_tokens >= max);
+
+        if (cost_exceeded || tokens_exceeded) && !*self.budget_exceeded_tx.borrow() {
+            let _ = self.budget_exceeded_tx.send(true);
+            return Some(usage);
+        }
+
+        None
+    }
+
+    pub fn usage(&self) -> Usage {
+        *self.usage.lock().unwrap()
+    }
+}
+
+/// A small table of known model prices, used to resolve a `TokenAccumulator`
+/// for a given model name at `RealtimeLogger` construction time.
+pub fn price_for_model(model: &str) -> ModelPrice {
+    match model {
+        "gpt-4.1" => ModelPrice {
+            input_per_1k: 0.002,
+            output_per_1k: 0.008,
+        },
+        "o3" => ModelPrice {
+            input_per_1k: 0.01,
+            output_per_1k: 0.04,
+        },
+        "gpt-4o-mini" | "o4-mini" => ModelPrice {
+            input_per_1k: 0.00015,
+            output_per_1k: 0.0006,
+        },
+        _ => ModelPrice {
+            input_per_1k: 0.0,
+            output_per_1k: 0.0,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_budget() -> Budget {
+        Budget::default()
+    }
+
+    #[test]
+    fn record_updates_usage_and_estimates_cost() {
+        let price = ModelPrice { input_per_1k: 0.01, output_per_1k: 0.04 };
+        let acc = TokenAccumulator::new(price, no_budget());
+
+        let exceeded = acc.record(1000, 500, 1500);
+
+        assert!(exceeded.is_none());
+        let usage = acc.usage();
+        assert_eq!(usage.input_tokens, 1000);
+        assert_eq!(usage.output_tokens, 500);
+        assert_eq!(usage.total_tokens, 1500);
+        assert!((usage.estimated_cost_usd - 0.03).abs() < 1e-9);
+    }
+
+    #[test]
+    fn record_fires_once_when_cost_budget_is_crossed() {
+        let price = ModelPrice { input_per_1k: 1.0, output_per_1k: 0.0 };
+        let budget = Budget { max_cost_usd: Some(1.0), max_total_tokens: None };
+        let acc = TokenAccumulator::new(price, budget);
+
+        assert!(acc.record(500, 0, 500).is_none());
+        let first = acc.record(1000, 0, 1000);
+        assert!(first.is_some());
+        // The signal only fires the first time the threshold is crossed.
+        assert!(acc.record(2000, 0, 2000).is_none());
+    }
+
+    #[test]
+    fn record_fires_when_token_budget_is_crossed() {
+        let price = ModelPrice { input_per_1k: 0.0, output_per_1k: 0.0 };
+        let budget = Budget { max_cost_usd: None, max_total_tokens: Some(100) };
+        let acc = TokenAccumulator::new(price, budget);
+
+        assert!(acc.record(10, 10, 20).is_none());
+        assert!(acc.record(60, 60, 120).is_some());
+    }
+
+    #[test]
+    fn subscribe_budget_exceeded_observes_the_signal() {
+        let budget = Budget { max_cost_usd: None, max_total_tokens: Some(10) };
+        let acc = TokenAccumulator::new(ModelPrice { input_per_1k: 0.0, output_per_1k: 0.0 }, budget);
+        let rx = acc.subscribe_budget_exceeded();
+
+        assert!(!*rx.borrow());
+        acc.record(5, 10, 15);
+        assert!(*rx.borrow());
+    }
+}